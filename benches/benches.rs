@@ -58,17 +58,19 @@ macro_rules! bench {
             let mut x = 0.;
             let mut y = 0.;
 
+            let api = f64::api();
+
             unsafe {
-                let s = symbol_table_new();
-                symbol_table_add_pi(s);
-                symbol_table_add_variable(s, c_string!("x"), &x as *const _, false);
-                symbol_table_add_variable(s, c_string!("y"), &y as *const _, false);
+                let s = (api.symbol_table_new)();
+                (api.symbol_table_add_pi)(s);
+                (api.symbol_table_add_variable)(s, c_string!("x"), &x as *const _, false);
+                (api.symbol_table_add_variable)(s, c_string!("y"), &y as *const _, false);
 
-                let e = expression_new();
-                expression_register_symbol_table(e, s);
+                let e = (api.expression_new)();
+                (api.expression_register_symbol_table)(e, s);
 
-                let p = parser_new();
-                parser_compile(p, c_string!($formula), e);
+                let p = (api.parser_new)();
+                (api.parser_compile)(p, c_string!($formula), e);
 
                 b.iter(|| {
                     let mut total = 0.;
@@ -78,13 +80,13 @@ macro_rules! bench {
                         x += DELTA;
                         while y < YMAX {
                             y += DELTA;
-                            total += expression_value(e);
+                            total += (api.expression_value)(e);
                         }
                     }
                 });
-                parser_destroy(p);
-                symbol_table_destroy(s);
-                expression_destroy(e);
+                (api.parser_destroy)(p);
+                (api.symbol_table_destroy)(s);
+                (api.expression_destroy)(e);
             }
         }
 
@@ -307,3 +309,51 @@ bench!(
         x * y
     }) + 2. * PI / x
 );
+
+// Evaluating a formula for rows of columnar data, either by setting the
+// variables from Rust for every row or with a single call to `eval_batch`
+
+const BATCH_FORMULA: &str = "(x^2 + y^2) / (2 * x * y)";
+const BATCH_ROWS: usize = 10000;
+
+fn batch_columns() -> (Vec<c_double>, Vec<c_double>) {
+    let x = (0..BATCH_ROWS)
+        .map(|i| 1. + i as c_double * DELTA)
+        .collect();
+    let y = (0..BATCH_ROWS)
+        .map(|i| 2. - i as c_double * DELTA)
+        .collect();
+    (x, y)
+}
+
+#[bench]
+fn batch_rows(b: &mut Bencher) {
+    let mut s = SymbolTable::new();
+    let x_id = s.add_variable("x", 0.).unwrap().unwrap();
+    let y_id = s.add_variable("y", 0.).unwrap().unwrap();
+    let mut e = Expression::new(BATCH_FORMULA, s).unwrap();
+    let (x, y) = batch_columns();
+    let mut out = vec![0.; BATCH_ROWS];
+
+    b.iter(|| {
+        for ((o, &x), &y) in out.iter_mut().zip(&x).zip(&y) {
+            e.symbols().value_cell(x_id).set(x);
+            e.symbols().value_cell(y_id).set(y);
+            *o = e.value();
+        }
+    });
+}
+
+#[bench]
+fn batch_eval(b: &mut Bencher) {
+    let mut s = SymbolTable::new();
+    let x_id = s.add_variable("x", 0.).unwrap().unwrap();
+    let y_id = s.add_variable("y", 0.).unwrap().unwrap();
+    let mut e = Expression::new(BATCH_FORMULA, s).unwrap();
+    let (x, y) = batch_columns();
+    let mut out = vec![0.; BATCH_ROWS];
+
+    b.iter(|| {
+        e.eval_batch(&[(x_id, &x), (y_id, &y)], &mut out);
+    });
+}
@@ -3,6 +3,10 @@ fn main() {
 
     c.file("cpp/wrapper.cpp").cpp(true);
 
+    if cfg!(feature = "complex") {
+        c.file("cpp/complex.cpp");
+    }
+
     if cfg!(target_os = "windows") {
         c.flag_if_supported("-bigobj");
         c.flag_if_supported("-Wa,-mbig-obj");
@@ -29,6 +33,9 @@ fn main() {
     if cfg!(not(feature = "superscalar_unroll")) {
         c.define("exprtk_disable_superscalar_unroll", Some("1"));
     }
+    if cfg!(not(feature = "rtl_io")) {
+        c.define("exprtk_disable_rtl_io", Some("1"));
+    }
     if cfg!(not(feature = "rtl_io_file")) {
         c.define("exprtk_disable_rtl_io_file", Some("1"));
     }
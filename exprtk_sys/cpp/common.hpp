@@ -0,0 +1,317 @@
+// Definitions shared by wrapper.cpp and complex.cpp. ExprTk (or the complex
+// adaptor) must be included before this file.
+
+#ifndef EXPRTK_RS_COMMON_HPP
+#define EXPRTK_RS_COMMON_HPP
+
+// helpers
+
+inline char *string_to_cstr(const std::string &s) {
+  char *pc = new char[s.size() + 1];
+  std::strcpy(pc, s.c_str());
+  return pc;
+}
+
+struct cstr_list {
+  size_t size;
+  char **elements;
+};
+
+inline cstr_list *strings_to_cstr_list(const std::vector<std::string> &v) {
+  cstr_list *out = new cstr_list;
+  out->size = v.size();
+  out->elements = new char *[out->size];
+
+  for (size_t i = 0; i < out->size; i++) {
+    out->elements[i] = string_to_cstr(v[i]);
+  }
+
+  return out;
+}
+
+// names and types of symbols collected by the dependent entity collector
+struct symbol_list {
+  size_t size;
+  char **names;
+  int *types;
+};
+
+template <typename T>
+symbol_list *symbols_to_list(
+    const std::deque<std::pair<std::string, T> > &symbols) {
+  symbol_list *out = new symbol_list;
+  out->size = symbols.size();
+  out->names = new char *[out->size];
+  out->types = new int[out->size];
+
+  for (size_t i = 0; i < out->size; i++) {
+    out->names[i] = string_to_cstr(symbols[i].first);
+    out->types[i] = (int)symbols[i].second;
+  }
+
+  return out;
+}
+
+// names and properties of the functions registered in a symbol table
+struct function_list {
+  size_t size;
+  char **names;
+  int *kinds;
+  // -1 if the number of arguments is variable
+  long *arities;
+  bool *pure;
+};
+
+inline function_list *new_function_list(size_t size) {
+  function_list *out = new function_list;
+  out->size = size;
+  out->names = new char *[size];
+  out->kinds = new int[size];
+  out->arities = new long[size];
+  out->pure = new bool[size];
+  return out;
+}
+
+extern "C" void free_rust_cstring(char *s);
+
+// for resolving unknown variables
+template <typename T>
+struct symbol_resolver : exprtk::parser<T>::unknown_symbol_resolver {
+  typedef typename exprtk::parser<T>::unknown_symbol_resolver usr_t;
+  char *(*callback)(const char *, void *);
+  void *user_data;
+
+  symbol_resolver(char *(*cb)(const char *, void *), void *d)
+      : usr_t(exprtk::parser<T>::unknown_symbol_resolver::e_usrmode_extended) {
+    callback = cb;
+    user_data = d;
+  }
+
+  virtual bool process(const std::string &unknown_symbol,
+                       exprtk::symbol_table<T> &symbol_table,
+                       std::string &error_message) {
+
+    // in bindings, only one symbol table is allowed per expression
+    // -> simplify things by ignoring this parameter
+    (void)symbol_table;
+
+    char *msg = (*callback)(unknown_symbol.c_str(), user_data);
+
+    if (msg != NULL) {
+      error_message = std::string(msg);
+      free_rust_cstring(msg);
+      return false;
+    }
+
+    return true;
+  }
+};
+
+struct parser_err {
+  bool is_err;
+  int mode;
+  const char *token_type;
+  const char *token_value;
+  // const char* mode;
+  const char *diagnostic;
+  const char *error_line;
+  size_t line_no;
+  size_t column_no;
+};
+
+struct func_result {
+  bool res;
+  void *fn_pointer;
+};
+
+// Simulating BOOST_PP_REPEAT macro for function definitions with
+// different numbers of scalar arguments.
+// https://www.boost.org/doc/libs/1_61_0/libs/preprocessor/doc/topics/techniques.html
+// Whether this is good practice could be debated; it certainly saves from
+// writing a lot of repetitive code
+#define REPEAT(n, m, p) REPEAT##n(m, p)
+
+#define REPEAT0(m, p)
+#define REPEAT1(m, p) m(0, p)
+#define REPEAT2(m, p) m(0, p), m(1, p)
+#define REPEAT3(m, p) REPEAT2(m, p), m(2, p)
+#define REPEAT4(m, p) REPEAT3(m, p), m(3, p)
+#define REPEAT5(m, p) REPEAT4(m, p), m(4, p)
+#define REPEAT6(m, p) REPEAT5(m, p), m(5, p)
+#define REPEAT7(m, p) REPEAT6(m, p), m(6, p)
+#define REPEAT8(m, p) REPEAT7(m, p), m(7, p)
+#define REPEAT9(m, p) REPEAT8(m, p), m(8, p)
+#define REPEAT10(m, p) REPEAT9(m, p), m(9, p)
+#define REPEAT11(m, p) REPEAT10(m, p), m(10, p)
+#define REPEAT12(m, p) REPEAT11(m, p), m(11, p)
+#define REPEAT13(m, p) REPEAT12(m, p), m(12, p)
+
+// for repeating the same arg
+#define SIMPLE(N, M) M
+// for appending an incrementing number
+#define NUMBERED(N, M) M##N
+// for indexing an array
+#define INDEXED(N, M) M[N]
+
+// Implementing exprtk::ifunction with different No of arguments
+// and providing FFI functions for Rust
+// The functions are assumed to have no side effects (not Fn() closures in Rust)
+#define FUNC_DEF(T, N)                                                         \
+  struct var##N##_func : public exprtk::ifunction<T> {                         \
+    T (*cb)(void *, REPEAT(N, SIMPLE, T));                                     \
+    void *user_data;                                                           \
+    var##N##_func(T (*c)(void *, REPEAT(N, SIMPLE, T)), void *d)               \
+        : exprtk::ifunction<T>(N) {                                            \
+      cb = c;                                                                  \
+      user_data = d;                                                           \
+      exprtk::disable_has_side_effects(*this);                                 \
+    }                                                                          \
+    T operator()(REPEAT(N, NUMBERED, const T &arg_)) {                         \
+      return cb(user_data, REPEAT(N, NUMBERED, arg_));                         \
+    }                                                                          \
+  };                                                                           \
+                                                                               \
+  func_result symbol_table_add_func##N(                                        \
+      SymbolTable *t, char *name, T (*cb)(void *, REPEAT(N, SIMPLE, T)),       \
+      void *user_data) {                                                       \
+    var##N##_func *f = new var##N##_func(cb, user_data);                       \
+    func_result out;                                                           \
+    std::string name_s = std::string(name);                                    \
+    out.res = t->add_function(name_s, *f);                                     \
+    if (!out.res) {                                                            \
+      delete f;                                                                \
+    } else {                                                                   \
+      out.fn_pointer = (void *)f;                                              \
+    }                                                                          \
+    return out;                                                                \
+  }                                                                            \
+                                                                               \
+  void symbol_table_free_func##N(void *f) { delete (var##N##_func *)f; }
+
+// Polynomial of one variable with fixed coefficients (highest degree first),
+// evaluated by an exprtk::polynomial of the same degree, which takes the
+// coefficients as arguments
+#define POLYNOMIAL_MAX_DEGREE 12
+
+#define POLY_NEW(N) case N: return new exprtk::polynomial<T, N>;
+#define POLY_CALL(N, NC) case N: return (*poly)(x, REPEAT(NC, INDEXED, c));
+
+template <typename T> struct coeff_polynomial : public exprtk::ifunction<T> {
+  std::vector<T> coeffs;
+  exprtk::ifunction<T> *poly;
+
+  coeff_polynomial(const T *c, size_t n)
+      : exprtk::ifunction<T>(1), coeffs(c, c + n), poly(new_poly(n - 1)) {
+    exprtk::disable_has_side_effects(*this);
+  }
+
+  ~coeff_polynomial() { delete poly; }
+
+  static exprtk::ifunction<T> *new_poly(size_t degree) {
+    switch (degree) {
+      POLY_NEW(1) POLY_NEW(2) POLY_NEW(3) POLY_NEW(4) POLY_NEW(5) POLY_NEW(6)
+      POLY_NEW(7) POLY_NEW(8) POLY_NEW(9) POLY_NEW(10) POLY_NEW(11) POLY_NEW(12)
+    default:
+      return NULL;
+    }
+  }
+
+  T operator()(const T &x) {
+    const T *c = coeffs.data();
+    switch (coeffs.size() - 1) {
+      POLY_CALL(1, 2) POLY_CALL(2, 3) POLY_CALL(3, 4) POLY_CALL(4, 5)
+      POLY_CALL(5, 6) POLY_CALL(6, 7) POLY_CALL(7, 8) POLY_CALL(8, 9)
+      POLY_CALL(9, 10) POLY_CALL(10, 11) POLY_CALL(11, 12) POLY_CALL(12, 13)
+    default:
+      // degree 0
+      return c[0];
+    }
+  }
+
+private:
+  coeff_polynomial(const coeff_polynomial &);
+  coeff_polynomial &operator=(const coeff_polynomial &);
+};
+
+// Callback receiving output of the IO package
+typedef void (*write_cb)(void *, const char *, size_t);
+
+#ifndef exprtk_disable_rtl_io
+// Replacement for print() and println() of exprtk::rtl::io, writing to a
+// callback instead of stdout. The output format is the same.
+template <typename T> struct io_print : public exprtk::igeneric_function<T> {
+  typedef exprtk::igeneric_function<T> igfun_t;
+  typedef typename igfun_t::parameter_list_t parameter_list_t;
+  typedef typename igfun_t::generic_type generic_type;
+  typedef typename generic_type::scalar_view scalar_t;
+  typedef typename generic_type::vector_view vector_t;
+  typedef typename generic_type::string_view string_t;
+
+  write_cb cb;
+  void *user_data;
+  bool newline;
+
+  io_print(write_cb c, void *d, bool nl) : cb(c), user_data(d), newline(nl) {
+    exprtk::enable_zero_parameters(*this);
+  }
+
+  T operator()(parameter_list_t parameters) {
+    std::string out;
+    for (std::size_t i = 0; i < parameters.size(); ++i) {
+      generic_type &gt = parameters[i];
+      switch (gt.type) {
+      case generic_type::e_scalar:
+        append_scalar(out, scalar_t(gt)());
+        break;
+      case generic_type::e_vector: {
+        vector_t v(gt);
+        for (std::size_t j = 0; j < v.size(); ++j) {
+          if (j > 0) {
+            out += ' ';
+          }
+          append_scalar(out, v[j]);
+        }
+        break;
+      }
+      case generic_type::e_string:
+        out += exprtk::to_str(string_t(gt));
+        break;
+      default:
+        break;
+      }
+    }
+    if (newline) {
+      out += '\n';
+    }
+    cb(user_data, out.data(), out.size());
+    return T(0);
+  }
+
+  static void append_scalar(std::string &out, const T &v) {
+    const char *fmt = "%10.5f";
+    int n = snprintf(NULL, 0, fmt, (double)v);
+    if (n > 0) {
+      std::vector<char> buf(n + 1);
+      snprintf(&buf[0], buf.size(), fmt, (double)v);
+      out.append(&buf[0], n);
+    }
+  }
+};
+
+template <typename T> struct io_package {
+  io_print<T> print;
+  io_print<T> println;
+
+  io_package(write_cb cb, void *user_data)
+      : print(cb, user_data, false), println(cb, user_data, true) {}
+};
+#endif
+
+// The methods depending on a specific numeric type are defined in api.inc,
+// which is included once for every supported type. Each namespace provides
+// a table of pointers to these functions (api_table), which is handed over
+// to Rust.
+#define API_FIELD(name) decltype(&fns::name) name;
+#define API_INIT(name) &fns::name,
+
+#endif
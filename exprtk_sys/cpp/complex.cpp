@@ -0,0 +1,22 @@
+// Complex number support, compiled with the `complex` feature
+
+// ExprTk has to be configured like in wrapper.cpp (by the build script), since
+// both translation units share its inline functions and templates. The runtime
+// library packages are therefore only left out in api.inc: the vector
+// operations (sorting etc.) require an ordered type, and scalars are printed
+// using printf formats, which are not available for complex numbers.
+#define EXPRTK_RS_NO_RTL_PACKAGES
+
+#include "complex_adaptor.hpp"
+
+#include "common.hpp"
+
+namespace c64 {
+typedef cplx::complex_t real;
+#include "api.inc"
+} // namespace c64
+
+extern "C" {
+
+const c64::api_table *exprtk_api_c64() { return &c64::api; }
+}
@@ -0,0 +1,375 @@
+// Numeric type adaptor instantiating ExprTk with cplx::complex_t, following
+// the approach of the custom type adaptors distributed with ExprTk
+// (e.g. exprtk_real_adaptor.hpp). This header must be included instead of
+// exprtk.hpp, since some declarations have to precede the library.
+//
+// Transcendental functions are evaluated with std::complex<double>.
+// Rounding functions work on both parts separately, while functions only
+// defined for real numbers (erf, erfc, ncdf, atan2) use the real part.
+
+#ifndef EXPRTK_RS_COMPLEX_ADAPTOR_HPP
+#define EXPRTK_RS_COMPLEX_ADAPTOR_HPP
+
+#include <string>
+
+#include "complex_type.hpp"
+
+namespace exprtk {
+namespace details {
+namespace numeric {
+namespace details {
+struct complex_type_tag;
+
+template <typename T> inline T const_pi_impl(complex_type_tag);
+template <typename T> inline T const_e_impl(complex_type_tag);
+} // namespace details
+} // namespace numeric
+
+inline bool is_true(const cplx::complex_t v);
+inline bool is_false(const cplx::complex_t v);
+
+template <typename Iterator>
+inline bool string_to_real(Iterator &itr_external, const Iterator end,
+                           cplx::complex_t &t,
+                           numeric::details::complex_type_tag);
+} // namespace details
+
+using details::is_true;
+} // namespace exprtk
+
+#include "exprtk/exprtk.hpp"
+
+namespace exprtk {
+namespace details {
+namespace numeric {
+namespace details {
+
+typedef cplx::complex_t ctype;
+
+struct complex_type_tag {
+  complex_type_tag() {}
+};
+
+template <> struct number_type<ctype> {
+  typedef complex_type_tag type;
+  number_type() {}
+};
+
+template <> struct epsilon_type<ctype> {
+  static inline ctype value() { return ctype(0.0000000001); }
+};
+
+// applies a function of double to both parts of a complex number
+template <typename F> inline ctype parts(const ctype v, F f) {
+  return ctype(f(v.re), f(v.im));
+}
+
+inline double round_d(const double v) {
+  return (v < 0.0) ? std::ceil(v - 0.5) : std::floor(v + 0.5);
+}
+
+inline double trunc_d(const double v) {
+  return (v < 0.0) ? std::ceil(v) : std::floor(v);
+}
+
+template <typename T> inline T const_pi_impl(complex_type_tag) {
+  return T(constant::pi);
+}
+
+template <typename T> inline T const_e_impl(complex_type_tag) {
+  return T(constant::e);
+}
+
+inline bool is_nan_impl(const ctype v, complex_type_tag) {
+  return (v.re != v.re) || (v.im != v.im);
+}
+
+inline int to_int32_impl(const ctype v, complex_type_tag) {
+  return static_cast<int>(v.re);
+}
+
+inline _int64_t to_int64_impl(const ctype v, complex_type_tag) {
+  return static_cast<_int64_t>(v.re);
+}
+
+inline _uint64_t to_uint64_impl(const ctype v, complex_type_tag) {
+  return static_cast<_uint64_t>(v.re);
+}
+
+inline bool is_integer_impl(const ctype v, complex_type_tag) {
+  return (v.im == 0.0) && (std::floor(v.re) == v.re);
+}
+
+// unary functions
+
+inline ctype abs_impl(const ctype v, complex_type_tag) {
+  return std::abs(v.to_std());
+}
+
+inline ctype acos_impl(const ctype v, complex_type_tag) {
+  return std::acos(v.to_std());
+}
+
+inline ctype acosh_impl(const ctype v, complex_type_tag) {
+  return std::acosh(v.to_std());
+}
+
+inline ctype asin_impl(const ctype v, complex_type_tag) {
+  return std::asin(v.to_std());
+}
+
+inline ctype asinh_impl(const ctype v, complex_type_tag) {
+  return std::asinh(v.to_std());
+}
+
+inline ctype atan_impl(const ctype v, complex_type_tag) {
+  return std::atan(v.to_std());
+}
+
+inline ctype atanh_impl(const ctype v, complex_type_tag) {
+  return std::atanh(v.to_std());
+}
+
+inline ctype cos_impl(const ctype v, complex_type_tag) {
+  return std::cos(v.to_std());
+}
+
+inline ctype cosh_impl(const ctype v, complex_type_tag) {
+  return std::cosh(v.to_std());
+}
+
+inline ctype exp_impl(const ctype v, complex_type_tag) {
+  return std::exp(v.to_std());
+}
+
+inline ctype expm1_impl(const ctype v, complex_type_tag) {
+  return std::exp(v.to_std()) - 1.0;
+}
+
+inline ctype log_impl(const ctype v, complex_type_tag) {
+  return std::log(v.to_std());
+}
+
+inline ctype log10_impl(const ctype v, complex_type_tag) {
+  return std::log10(v.to_std());
+}
+
+inline ctype log2_impl(const ctype v, complex_type_tag) {
+  return std::log(v.to_std()) / constant::log2;
+}
+
+inline ctype log1p_impl(const ctype v, complex_type_tag) {
+  return std::log(1.0 + v.to_std());
+}
+
+inline ctype sin_impl(const ctype v, complex_type_tag) {
+  return std::sin(v.to_std());
+}
+
+inline ctype sinh_impl(const ctype v, complex_type_tag) {
+  return std::sinh(v.to_std());
+}
+
+inline ctype sqrt_impl(const ctype v, complex_type_tag) {
+  return std::sqrt(v.to_std());
+}
+
+inline ctype tan_impl(const ctype v, complex_type_tag) {
+  return std::tan(v.to_std());
+}
+
+inline ctype tanh_impl(const ctype v, complex_type_tag) {
+  return std::tanh(v.to_std());
+}
+
+inline ctype cot_impl(const ctype v, complex_type_tag) {
+  return 1.0 / std::tan(v.to_std());
+}
+
+inline ctype sec_impl(const ctype v, complex_type_tag) {
+  return 1.0 / std::cos(v.to_std());
+}
+
+inline ctype csc_impl(const ctype v, complex_type_tag) {
+  return 1.0 / std::sin(v.to_std());
+}
+
+inline ctype sinc_impl(const ctype v, complex_type_tag) {
+  if (std::abs(v.to_std()) >= std::numeric_limits<double>::epsilon())
+    return std::sin(v.to_std()) / v.to_std();
+  else
+    return ctype(1.0);
+}
+
+inline ctype sgn_impl(const ctype v, complex_type_tag) {
+  const double a = std::abs(v.to_std());
+  return (a == 0.0) ? ctype(0.0) : ctype(v.re / a, v.im / a);
+}
+
+inline ctype neg_impl(const ctype v, complex_type_tag) { return -v; }
+
+inline ctype pos_impl(const ctype v, complex_type_tag) { return v; }
+
+inline ctype notl_impl(const ctype v, complex_type_tag) {
+  return (v != ctype(0.0)) ? ctype(0.0) : ctype(1.0);
+}
+
+inline ctype r2d_impl(const ctype v, complex_type_tag) {
+  return v * constant::_180_pi;
+}
+
+inline ctype d2r_impl(const ctype v, complex_type_tag) {
+  return v * constant::pi_180;
+}
+
+inline ctype d2g_impl(const ctype v, complex_type_tag) {
+  return v * (10.0 / 9.0);
+}
+
+inline ctype g2d_impl(const ctype v, complex_type_tag) {
+  return v * (9.0 / 10.0);
+}
+
+inline ctype ceil_impl(const ctype v, complex_type_tag) {
+  return parts(v, static_cast<double (*)(double)>(std::ceil));
+}
+
+inline ctype floor_impl(const ctype v, complex_type_tag) {
+  return parts(v, static_cast<double (*)(double)>(std::floor));
+}
+
+inline ctype round_impl(const ctype v, complex_type_tag) {
+  return parts(v, round_d);
+}
+
+inline ctype trunc_impl(const ctype v, complex_type_tag) {
+  return parts(v, trunc_d);
+}
+
+inline ctype frac_impl(const ctype v, complex_type_tag) {
+  return v - parts(v, trunc_d);
+}
+
+inline ctype erf_impl(const ctype v, complex_type_tag) {
+  return erf_impl(v.re, real_type_tag());
+}
+
+inline ctype erfc_impl(const ctype v, complex_type_tag) {
+  return erfc_impl(v.re, real_type_tag());
+}
+
+inline ctype ncdf_impl(const ctype v, complex_type_tag) {
+  return ncdf_impl(v.re, real_type_tag());
+}
+
+// binary functions
+
+inline ctype min_impl(const ctype v0, const ctype v1, complex_type_tag) {
+  return (v1 < v0) ? v1 : v0;
+}
+
+inline ctype max_impl(const ctype v0, const ctype v1, complex_type_tag) {
+  return (v0 < v1) ? v1 : v0;
+}
+
+inline ctype equal_impl(const ctype v0, const ctype v1, complex_type_tag) {
+  const double epsilon = epsilon_type<double>::value();
+  const double scale = std::max(
+      1.0, std::max(std::abs(v0.to_std()), std::abs(v1.to_std())));
+  return (std::abs((v0 - v1).to_std()) <= scale * epsilon) ? ctype(1.0)
+                                                          : ctype(0.0);
+}
+
+inline ctype nequal_impl(const ctype v0, const ctype v1, complex_type_tag) {
+  return notl_impl(equal_impl(v0, v1, complex_type_tag()), complex_type_tag());
+}
+
+inline ctype modulus_impl(const ctype v0, const ctype v1, complex_type_tag) {
+  return ctype(std::fmod(v0.re, v1.re), std::fmod(v0.im, v1.im));
+}
+
+inline ctype pow_impl(const ctype v0, const ctype v1, complex_type_tag) {
+  return std::pow(v0.to_std(), v1.to_std());
+}
+
+inline ctype logn_impl(const ctype v0, const ctype v1, complex_type_tag) {
+  return std::log(v0.to_std()) / std::log(v1.to_std());
+}
+
+inline ctype root_impl(const ctype v0, const ctype v1, complex_type_tag) {
+  return std::pow(v0.to_std(), 1.0 / v1.to_std());
+}
+
+inline ctype roundn_impl(const ctype v0, const ctype v1, complex_type_tag) {
+  const double p10 = std::pow(10.0, std::floor(v1.re));
+  return ctype(round_d(v0.re * p10) / p10, round_d(v0.im * p10) / p10);
+}
+
+inline ctype hypot_impl(const ctype v0, const ctype v1, complex_type_tag) {
+  return std::sqrt(v0.to_std() * v0.to_std() + v1.to_std() * v1.to_std());
+}
+
+inline ctype atan2_impl(const ctype v0, const ctype v1, complex_type_tag) {
+  return std::atan2(v0.re, v1.re);
+}
+
+inline ctype shr_impl(const ctype v0, const ctype v1, complex_type_tag) {
+  return v0 * std::pow(2.0, -v1.re);
+}
+
+inline ctype shl_impl(const ctype v0, const ctype v1, complex_type_tag) {
+  return v0 * std::pow(2.0, v1.re);
+}
+
+inline ctype and_impl(const ctype v0, const ctype v1, complex_type_tag) {
+  return (is_true(v0) && is_true(v1)) ? ctype(1.0) : ctype(0.0);
+}
+
+inline ctype nand_impl(const ctype v0, const ctype v1, complex_type_tag) {
+  return (is_true(v0) && is_true(v1)) ? ctype(0.0) : ctype(1.0);
+}
+
+inline ctype or_impl(const ctype v0, const ctype v1, complex_type_tag) {
+  return (is_true(v0) || is_true(v1)) ? ctype(1.0) : ctype(0.0);
+}
+
+inline ctype nor_impl(const ctype v0, const ctype v1, complex_type_tag) {
+  return (is_true(v0) || is_true(v1)) ? ctype(0.0) : ctype(1.0);
+}
+
+inline ctype xor_impl(const ctype v0, const ctype v1, complex_type_tag) {
+  return (is_true(v0) != is_true(v1)) ? ctype(1.0) : ctype(0.0);
+}
+
+inline ctype xnor_impl(const ctype v0, const ctype v1, complex_type_tag) {
+  return (is_true(v0) == is_true(v1)) ? ctype(1.0) : ctype(0.0);
+}
+
+} // namespace details
+} // namespace numeric
+
+inline bool is_true(const cplx::complex_t v) {
+  return v != cplx::complex_t(0.0);
+}
+
+inline bool is_false(const cplx::complex_t v) {
+  return v == cplx::complex_t(0.0);
+}
+
+// numbers in formulas are always real, imaginary numbers are obtained
+// by multiplication with a constant
+template <typename Iterator>
+inline bool string_to_real(Iterator &itr_external, const Iterator end,
+                           cplx::complex_t &t,
+                           numeric::details::complex_type_tag) {
+  double re = 0.0;
+  if (!string_to_real(itr_external, end, re, numeric::details::real_type_tag()))
+    return false;
+  t = cplx::complex_t(re);
+  return true;
+}
+
+} // namespace details
+} // namespace exprtk
+
+#endif
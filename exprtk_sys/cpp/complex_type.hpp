@@ -0,0 +1,134 @@
+// Complex number type used for instantiating the ExprTk templates.
+//
+// The memory layout (two doubles: real and imaginary part) corresponds to
+// num_complex::Complex64 in Rust, values can therefore be passed by value and
+// by pointer between the two languages. The type must stay trivially copyable.
+//
+// Comparisons other than (in)equality only consider the real part, which
+// allows using the conditional and logical operators of ExprTk.
+
+#ifndef EXPRTK_RS_COMPLEX_TYPE_HPP
+#define EXPRTK_RS_COMPLEX_TYPE_HPP
+
+#include <cmath>
+#include <complex>
+#include <limits>
+#include <type_traits>
+
+namespace cplx {
+
+struct complex_t {
+  double re;
+  double im;
+
+  complex_t(const double r = 0.0, const double i = 0.0) : re(r), im(i) {}
+
+  complex_t(const std::complex<double> &c) : re(c.real()), im(c.imag()) {}
+
+  std::complex<double> to_std() const { return std::complex<double>(re, im); }
+
+  // conversion to the arithmetic types takes the real part
+  template <typename U, typename = typename std::enable_if<
+                            std::is_arithmetic<U>::value>::type>
+  explicit operator U() const {
+    return static_cast<U>(re);
+  }
+
+  complex_t &operator+=(const complex_t &o) {
+    re += o.re;
+    im += o.im;
+    return *this;
+  }
+
+  complex_t &operator-=(const complex_t &o) {
+    re -= o.re;
+    im -= o.im;
+    return *this;
+  }
+
+  complex_t &operator*=(const complex_t &o) {
+    *this = to_std() * o.to_std();
+    return *this;
+  }
+
+  complex_t &operator/=(const complex_t &o) {
+    *this = to_std() / o.to_std();
+    return *this;
+  }
+};
+
+inline complex_t operator+(const complex_t &a, const complex_t &b) {
+  return complex_t(a.re + b.re, a.im + b.im);
+}
+
+inline complex_t operator-(const complex_t &a, const complex_t &b) {
+  return complex_t(a.re - b.re, a.im - b.im);
+}
+
+inline complex_t operator*(const complex_t &a, const complex_t &b) {
+  return a.to_std() * b.to_std();
+}
+
+inline complex_t operator/(const complex_t &a, const complex_t &b) {
+  return a.to_std() / b.to_std();
+}
+
+inline complex_t operator-(const complex_t &a) { return complex_t(-a.re, -a.im); }
+
+inline complex_t operator+(const complex_t &a) { return a; }
+
+inline bool operator==(const complex_t &a, const complex_t &b) {
+  return a.re == b.re && a.im == b.im;
+}
+
+inline bool operator!=(const complex_t &a, const complex_t &b) {
+  return !(a == b);
+}
+
+inline bool operator<(const complex_t &a, const complex_t &b) {
+  return a.re < b.re;
+}
+
+inline bool operator<=(const complex_t &a, const complex_t &b) {
+  return a.re <= b.re;
+}
+
+inline bool operator>(const complex_t &a, const complex_t &b) {
+  return a.re > b.re;
+}
+
+inline bool operator>=(const complex_t &a, const complex_t &b) {
+  return a.re >= b.re;
+}
+
+} // namespace cplx
+
+namespace std {
+
+template <> class numeric_limits<cplx::complex_t> {
+  typedef numeric_limits<double> base;
+
+public:
+  static const bool is_specialized = true;
+  static const bool is_signed = true;
+  static const bool is_integer = false;
+  static const bool is_exact = false;
+  static const bool has_infinity = true;
+  static const bool has_quiet_NaN = true;
+  static const int digits = base::digits;
+  static const int digits10 = base::digits10;
+
+  static cplx::complex_t min() { return base::min(); }
+  static cplx::complex_t max() { return base::max(); }
+  static cplx::complex_t lowest() { return base::lowest(); }
+  static cplx::complex_t epsilon() { return base::epsilon(); }
+  static cplx::complex_t round_error() { return base::round_error(); }
+  static cplx::complex_t infinity() { return base::infinity(); }
+  static cplx::complex_t quiet_NaN() { return base::quiet_NaN(); }
+  static cplx::complex_t signaling_NaN() { return base::signaling_NaN(); }
+  static cplx::complex_t denorm_min() { return base::denorm_min(); }
+};
+
+} // namespace std
+
+#endif
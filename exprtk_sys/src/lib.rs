@@ -2,6 +2,8 @@
 
 use libc::*;
 use std::ffi::CString;
+use std::fmt::{Debug, Display};
+use std::ops::{Add, Div, Mul, Neg, Sub};
 use std::slice;
 
 // types
@@ -10,6 +12,7 @@ pub enum CSymbolTable {}
 pub enum CExpression {}
 pub enum CParser {}
 pub enum CppString {}
+pub enum CVectorView {}
 
 // simple types used for communications with C++
 
@@ -24,6 +27,77 @@ impl CStrList {
     }
 }
 
+#[repr(C)]
+pub struct CSymbolList {
+    pub size: size_t,
+    pub names: *const *const c_char,
+    pub types: *const c_int,
+}
+
+impl CSymbolList {
+    pub unsafe fn get_names(&self) -> &[*const c_char] {
+        slice::from_raw_parts(self.names, self.size as usize)
+    }
+
+    pub unsafe fn get_types(&self) -> &[c_int] {
+        slice::from_raw_parts(self.types, self.size as usize)
+    }
+}
+
+#[repr(C)]
+pub struct CFunctionList {
+    pub size: size_t,
+    pub names: *const *const c_char,
+    pub kinds: *const c_int,
+    pub arities: *const c_long,
+    pub pure: *const bool,
+}
+
+impl CFunctionList {
+    pub unsafe fn get_names(&self) -> &[*const c_char] {
+        slice::from_raw_parts(self.names, self.size as usize)
+    }
+
+    pub unsafe fn get_kinds(&self) -> &[c_int] {
+        slice::from_raw_parts(self.kinds, self.size as usize)
+    }
+
+    pub unsafe fn get_arities(&self) -> &[c_long] {
+        slice::from_raw_parts(self.arities, self.size as usize)
+    }
+
+    pub unsafe fn get_pure(&self) -> &[bool] {
+        slice::from_raw_parts(self.pure, self.size as usize)
+    }
+}
+
+#[repr(C)]
+pub struct CTokenList {
+    pub size: size_t,
+    pub kinds: *const c_int,
+    pub values: *const *const c_char,
+    pub starts: *const size_t,
+    pub ends: *const size_t,
+}
+
+impl CTokenList {
+    pub unsafe fn get_kinds(&self) -> &[c_int] {
+        slice::from_raw_parts(self.kinds, self.size as usize)
+    }
+
+    pub unsafe fn get_values(&self) -> &[*const c_char] {
+        slice::from_raw_parts(self.values, self.size as usize)
+    }
+
+    pub unsafe fn get_starts(&self) -> &[size_t] {
+        slice::from_raw_parts(self.starts, self.size as usize)
+    }
+
+    pub unsafe fn get_ends(&self) -> &[size_t] {
+        slice::from_raw_parts(self.ends, self.size as usize)
+    }
+}
+
 #[repr(C)]
 pub struct CParseError {
     pub is_err: bool,
@@ -42,259 +116,346 @@ pub unsafe extern "C" fn free_rust_cstring(s: *mut c_char) {
     let _ = CString::from_raw(s);
 }
 
-// functions without polymorphism
-extern "C" {
+mod private {
+    pub trait Sealed {}
+
+    impl Sealed for f32 {}
+    impl Sealed for f64 {}
+    #[cfg(feature = "complex")]
+    impl Sealed for num_complex::Complex64 {}
+}
+
+/// Numeric types, for which the ExprTk templates are instantiated
+/// (`f32`, `f64` and `Complex64` with the `complex` feature). The functions
+/// depending on the type are accessed through the table returned by `Numeric::api()`.
+///
+/// `long double` is not supported, since Rust has no equivalent type.
+pub trait Numeric:
+    private::Sealed
+    + Copy
+    + Default
+    + PartialEq
+    + Debug
+    + Display
+    + Send
+    + Sync
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Neg<Output = Self>
+    + 'static
+{
+    fn api() -> &'static Api<Self>;
+
+    fn from_f64(value: f64) -> Self;
+}
 
-    // these methods depend on a specific precision
+/// Real floating point types (`f32` and `f64`)
+pub trait Real: Numeric + PartialOrd {
+    fn to_f64(self) -> f64;
+}
+
+impl Numeric for f32 {
+    #[inline]
+    fn api() -> &'static Api<f32> {
+        unsafe { &*exprtk_api_f32() }
+    }
 
-    pub fn symbol_table_new() -> *mut CSymbolTable;
-    pub fn symbol_table_add_variable(
+    #[inline]
+    fn from_f64(value: f64) -> Self {
+        value as f32
+    }
+}
+
+impl Real for f32 {
+    #[inline]
+    fn to_f64(self) -> f64 {
+        self as f64
+    }
+}
+
+impl Numeric for f64 {
+    #[inline]
+    fn api() -> &'static Api<f64> {
+        unsafe { &*exprtk_api_f64() }
+    }
+
+    #[inline]
+    fn from_f64(value: f64) -> Self {
+        value
+    }
+}
+
+impl Real for f64 {
+    #[inline]
+    fn to_f64(self) -> f64 {
+        self
+    }
+}
+
+/// Complex numbers are evaluated using an adaptor type (`cpp/complex_type.hpp`)
+/// with the same memory layout as `Complex64`.
+#[cfg(feature = "complex")]
+impl Numeric for num_complex::Complex64 {
+    #[inline]
+    fn api() -> &'static Api<num_complex::Complex64> {
+        unsafe { &*exprtk_api_c64() }
+    }
+
+    #[inline]
+    fn from_f64(value: f64) -> Self {
+        num_complex::Complex64::new(value, 0.)
+    }
+}
+
+/// Table of the functions depending on a specific precision. The order of the
+/// fields must correspond to `API_FUNCTIONS` in `cpp/api.inc`.
+#[repr(C)]
+pub struct Api<T> {
+    // Parser
+    pub parser_new: unsafe extern "C" fn() -> *mut CParser,
+    pub parser_destroy: unsafe extern "C" fn(p: *mut CParser),
+    pub parser_compile:
+        unsafe extern "C" fn(p: *mut CParser, s: *const c_char, e: *const CExpression) -> bool,
+    pub parser_compile_resolve: unsafe extern "C" fn(
+        p: *mut CParser,
+        s: *const c_char,
+        e: *const CExpression,
+        cb: extern "C" fn(*const c_char, *mut c_void) -> *const c_char,
+        fn_pointer: *mut c_void,
+    ) -> bool,
+    pub parser_collect_assignments: unsafe extern "C" fn(p: *mut CParser, collect: bool),
+    pub parser_assignment_symbols: unsafe extern "C" fn(p: *mut CParser) -> *mut CSymbolList,
+    pub parser_error: unsafe extern "C" fn(p: *mut CParser) -> *const CParseError,
+
+    // Symbol table
+    pub symbol_table_new: unsafe extern "C" fn() -> *mut CSymbolTable,
+    pub symbol_table_destroy: unsafe extern "C" fn(t: *mut CSymbolTable),
+    pub symbol_table_add_variable: unsafe extern "C" fn(
         t: *mut CSymbolTable,
         variable_name: *const c_char,
-        value: *const c_double,
+        value: *const T,
         is_constant: bool,
-    ) -> bool;
-    pub fn symbol_table_add_constant(
-        t: *mut CSymbolTable,
-        variable_name: *const c_char,
-        value: c_double,
-    ) -> bool;
-    pub fn symbol_table_create_variable(
-        t: *mut CSymbolTable,
-        variable_name: *const c_char,
-        value: c_double,
-    ) -> bool;
-    pub fn symbol_table_add_stringvar(
+    ) -> bool,
+    pub symbol_table_create_variable:
+        unsafe extern "C" fn(t: *mut CSymbolTable, variable_name: *const c_char, value: T) -> bool,
+    pub symbol_table_add_constant:
+        unsafe extern "C" fn(t: *mut CSymbolTable, variable_name: *const c_char, value: T) -> bool,
+    pub symbol_table_add_stringvar: unsafe extern "C" fn(
         t: *mut CSymbolTable,
         variable_name: *const c_char,
         string: *mut CppString,
         is_const: bool,
-    ) -> bool;
-    pub fn symbol_table_create_stringvar(
+    ) -> bool,
+    pub symbol_table_create_stringvar: unsafe extern "C" fn(
         t: *mut CSymbolTable,
         variable_name: *const c_char,
         string: *const c_char,
-    ) -> bool;
-    pub fn symbol_table_add_vector(
+    ) -> bool,
+    pub symbol_table_add_vector: unsafe extern "C" fn(
         t: *mut CSymbolTable,
         variable_name: *const c_char,
-        ptr: *const c_double,
+        ptr: *const T,
         len: size_t,
-    ) -> bool;
-    pub fn symbol_table_remove_variable(t: *mut CSymbolTable, name: *const c_char) -> bool;
-    pub fn symbol_table_remove_stringvar(t: *mut CSymbolTable, name: *const c_char) -> bool;
-    pub fn symbol_table_remove_vector(t: *mut CSymbolTable, name: *const c_char) -> bool;
-    pub fn symbol_table_clear_variables(t: *mut CSymbolTable);
-    pub fn symbol_table_clear_strings(t: *mut CSymbolTable);
-    pub fn symbol_table_clear_vectors(t: *mut CSymbolTable);
-    pub fn symbol_table_clear_local_constants(t: *mut CSymbolTable);
-    pub fn symbol_table_clear_functions(t: *mut CSymbolTable);
-    pub fn symbol_table_variable_ref(
-        t: *mut CSymbolTable,
-        variable_name: *const c_char,
-    ) -> *mut c_double;
-    pub fn symbol_table_stringvar_ref(
-        t: *mut CSymbolTable,
-        variable_name: *const c_char,
-    ) -> *mut CppString;
-    pub fn symbol_table_vector_ptr(
+    ) -> bool,
+    pub symbol_table_remove_variable:
+        unsafe extern "C" fn(t: *mut CSymbolTable, name: *const c_char) -> bool,
+    pub symbol_table_remove_stringvar:
+        unsafe extern "C" fn(t: *mut CSymbolTable, name: *const c_char) -> bool,
+    pub symbol_table_remove_vector:
+        unsafe extern "C" fn(t: *mut CSymbolTable, name: *const c_char) -> bool,
+    pub symbol_table_clear_variables: unsafe extern "C" fn(t: *mut CSymbolTable),
+    pub symbol_table_clear_strings: unsafe extern "C" fn(t: *mut CSymbolTable),
+    pub symbol_table_clear_vectors: unsafe extern "C" fn(t: *mut CSymbolTable),
+    pub symbol_table_clear_local_constants: unsafe extern "C" fn(t: *mut CSymbolTable),
+    pub symbol_table_clear_functions: unsafe extern "C" fn(t: *mut CSymbolTable),
+    pub symbol_table_variable_ref:
+        unsafe extern "C" fn(t: *mut CSymbolTable, variable_name: *const c_char) -> *mut T,
+    pub symbol_table_stringvar_ref:
+        unsafe extern "C" fn(t: *mut CSymbolTable, variable_name: *const c_char) -> *mut CppString,
+    pub symbol_table_vector_ptr:
+        unsafe extern "C" fn(t: *mut CSymbolTable, variable_name: *const c_char) -> *const T,
+    pub symbol_table_variable_count: unsafe extern "C" fn(t: *mut CSymbolTable) -> size_t,
+    pub symbol_table_stringvar_count: unsafe extern "C" fn(t: *mut CSymbolTable) -> size_t,
+    pub symbol_table_vector_count: unsafe extern "C" fn(t: *mut CSymbolTable) -> size_t,
+    pub symbol_table_function_count: unsafe extern "C" fn(t: *mut CSymbolTable) -> size_t,
+    pub symbol_table_add_constants: unsafe extern "C" fn(t: *mut CSymbolTable) -> bool,
+    pub symbol_table_add_pi: unsafe extern "C" fn(t: *mut CSymbolTable) -> bool,
+    pub symbol_table_add_epsilon: unsafe extern "C" fn(t: *mut CSymbolTable) -> bool,
+    pub symbol_table_add_infinity: unsafe extern "C" fn(t: *mut CSymbolTable) -> bool,
+    pub symbol_table_is_constant_node:
+        unsafe extern "C" fn(t: *mut CSymbolTable, name: *const c_char) -> bool,
+    pub symbol_table_is_constant_string:
+        unsafe extern "C" fn(t: *mut CSymbolTable, name: *const c_char) -> bool,
+    pub symbol_table_get_variable_list: unsafe extern "C" fn(t: *mut CSymbolTable) -> *mut CStrList,
+    pub symbol_table_get_stringvar_list:
+        unsafe extern "C" fn(t: *mut CSymbolTable) -> *mut CStrList,
+    pub symbol_table_get_vector_list: unsafe extern "C" fn(t: *mut CSymbolTable) -> *mut CStrList,
+    pub symbol_table_get_function_list:
+        unsafe extern "C" fn(t: *mut CSymbolTable) -> *mut CFunctionList,
+    pub symbol_table_symbol_exists:
+        unsafe extern "C" fn(t: *mut CSymbolTable, name: *const c_char) -> bool,
+    pub symbol_table_valid: unsafe extern "C" fn(t: *mut CSymbolTable) -> bool,
+    pub symbol_table_load_from:
+        unsafe extern "C" fn(t: *mut CSymbolTable, other: *const CSymbolTable),
+
+    // Runtime library packages
+    pub symbol_table_add_vecops_package: unsafe extern "C" fn(t: *mut CSymbolTable) -> bool,
+    pub symbol_table_add_io_package: unsafe extern "C" fn(
         t: *mut CSymbolTable,
-        variable_name: *const c_char,
-    ) -> *const c_double;
-    pub fn symbol_table_set_string(
+        cb: extern "C" fn(*mut c_void, *const c_char, size_t),
+        user_data: *mut c_void,
+    ) -> *mut c_void,
+    pub symbol_table_free_io_package: unsafe extern "C" fn(p: *mut c_void),
+
+    // Vector views
+    pub vector_view_new: unsafe extern "C" fn(data: *mut T, size: size_t) -> *mut CVectorView,
+    pub vector_view_destroy: unsafe extern "C" fn(v: *mut CVectorView),
+    pub vector_view_rebase: unsafe extern "C" fn(v: *mut CVectorView, data: *mut T),
+    pub vector_view_set_size: unsafe extern "C" fn(v: *mut CVectorView, size: size_t) -> bool,
+    pub symbol_table_add_vector_view: unsafe extern "C" fn(
         t: *mut CSymbolTable,
-        ptr: *const CppString,
-        string: *const c_char,
-    );
-    pub fn symbol_table_variable_count(t: *mut CSymbolTable) -> size_t;
-    pub fn symbol_table_stringvar_count(t: *mut CSymbolTable) -> size_t;
-    pub fn symbol_table_vector_count(t: *mut CSymbolTable) -> size_t;
-    pub fn symbol_table_function_count(t: *mut CSymbolTable) -> size_t;
-    pub fn symbol_table_add_pi(t: *mut CSymbolTable) -> bool;
-    pub fn symbol_table_add_epsilon(t: *mut CSymbolTable) -> bool;
-    pub fn symbol_table_add_infinity(t: *mut CSymbolTable) -> bool;
-    pub fn symbol_table_get_variable_list(t: *mut CSymbolTable) -> *mut CStrList;
-    pub fn symbol_table_get_stringvar_list(t: *mut CSymbolTable) -> *mut CStrList; //StringPtrList;
-    pub fn symbol_table_get_vector_list(t: *mut CSymbolTable) -> *mut CStrList;
-    pub fn symbol_table_valid(t: *mut CSymbolTable) -> bool;
-    pub fn symbol_table_symbol_exists(t: *mut CSymbolTable, name: *const c_char) -> bool;
-    pub fn symbol_table_load_from(t: *mut CSymbolTable, other: *const CSymbolTable);
-    pub fn symbol_table_add_constants(t: *mut CSymbolTable) -> bool;
-    pub fn symbol_table_is_constant_node(t: *mut CSymbolTable, name: *const c_char) -> bool;
-    pub fn symbol_table_is_constant_string(t: *mut CSymbolTable, name: *const c_char) -> bool;
-    pub fn symbol_table_destroy(t: *mut CSymbolTable);
-
-    // // blocked by #5668
-    // macro_rules! func_declare {
-    //     ($add_name:ident, $free_name:ident, $($ty:ty),*) => {
-    //         pub fn $add_name(t: *mut CSymbolTable, name: *const c_char,
-    //             cb: extern fn (*mut c_void, $($ty),*) -> c_double,
-    //             user_data: *mut c_void) -> Pair<bool, *mut c_void>;
-    //         pub fn $free_name(c_func: *mut c_void);
-    //     }
-    // }
-
-    pub fn symbol_table_add_func1(
+        name: *const c_char,
+        v: *mut CVectorView,
+    ) -> bool,
+
+    // Functions
+    pub symbol_table_add_func1: unsafe extern "C" fn(
         t: *mut CSymbolTable,
         name: *const c_char,
-        cb: extern "C" fn(*mut c_void, c_double) -> c_double,
+        cb: extern "C" fn(*mut c_void, T) -> T,
         user_data: *mut c_void,
-    ) -> Pair<bool, *mut c_void>;
-    pub fn symbol_table_free_func1(c_func: *mut c_void);
-
-    pub fn symbol_table_add_func2(
+    ) -> Pair<bool, *mut c_void>,
+    pub symbol_table_free_func1: unsafe extern "C" fn(c_func: *mut c_void),
+    pub symbol_table_add_func2: unsafe extern "C" fn(
         t: *mut CSymbolTable,
         name: *const c_char,
-        cb: extern "C" fn(*mut c_void, c_double, c_double) -> c_double,
+        cb: extern "C" fn(*mut c_void, T, T) -> T,
         user_data: *mut c_void,
-    ) -> Pair<bool, *mut c_void>;
-    pub fn symbol_table_free_func2(c_func: *mut c_void);
-
-    pub fn symbol_table_add_func3(
+    ) -> Pair<bool, *mut c_void>,
+    pub symbol_table_free_func2: unsafe extern "C" fn(c_func: *mut c_void),
+    pub symbol_table_add_func3: unsafe extern "C" fn(
         t: *mut CSymbolTable,
         name: *const c_char,
-        cb: extern "C" fn(*mut c_void, c_double, c_double, c_double) -> c_double,
+        cb: extern "C" fn(*mut c_void, T, T, T) -> T,
         user_data: *mut c_void,
-    ) -> Pair<bool, *mut c_void>;
-    pub fn symbol_table_free_func3(c_func: *mut c_void);
-
-    pub fn symbol_table_add_func4(
+    ) -> Pair<bool, *mut c_void>,
+    pub symbol_table_free_func3: unsafe extern "C" fn(c_func: *mut c_void),
+    pub symbol_table_add_func4: unsafe extern "C" fn(
         t: *mut CSymbolTable,
         name: *const c_char,
-        cb: extern "C" fn(*mut c_void, c_double, c_double, c_double, c_double) -> c_double,
+        cb: extern "C" fn(*mut c_void, T, T, T, T) -> T,
         user_data: *mut c_void,
-    ) -> Pair<bool, *mut c_void>;
-    pub fn symbol_table_free_func4(c_func: *mut c_void);
-
-    pub fn symbol_table_add_func5(
+    ) -> Pair<bool, *mut c_void>,
+    pub symbol_table_free_func4: unsafe extern "C" fn(c_func: *mut c_void),
+    pub symbol_table_add_func5: unsafe extern "C" fn(
         t: *mut CSymbolTable,
         name: *const c_char,
-        cb: extern "C" fn(
-            *mut c_void,
-            c_double,
-            c_double,
-            c_double,
-            c_double,
-            c_double,
-        ) -> c_double,
+        cb: extern "C" fn(*mut c_void, T, T, T, T, T) -> T,
         user_data: *mut c_void,
-    ) -> Pair<bool, *mut c_void>;
-    pub fn symbol_table_free_func5(c_func: *mut c_void);
-
-    pub fn symbol_table_add_func6(
+    ) -> Pair<bool, *mut c_void>,
+    pub symbol_table_free_func5: unsafe extern "C" fn(c_func: *mut c_void),
+    pub symbol_table_add_func6: unsafe extern "C" fn(
         t: *mut CSymbolTable,
         name: *const c_char,
-        cb: extern "C" fn(
-            *mut c_void,
-            c_double,
-            c_double,
-            c_double,
-            c_double,
-            c_double,
-            c_double,
-        ) -> c_double,
+        cb: extern "C" fn(*mut c_void, T, T, T, T, T, T) -> T,
         user_data: *mut c_void,
-    ) -> Pair<bool, *mut c_void>;
-    pub fn symbol_table_free_func6(c_func: *mut c_void);
-
-    pub fn symbol_table_add_func7(
+    ) -> Pair<bool, *mut c_void>,
+    pub symbol_table_free_func6: unsafe extern "C" fn(c_func: *mut c_void),
+    pub symbol_table_add_func7: unsafe extern "C" fn(
         t: *mut CSymbolTable,
         name: *const c_char,
-        cb: extern "C" fn(
-            *mut c_void,
-            c_double,
-            c_double,
-            c_double,
-            c_double,
-            c_double,
-            c_double,
-            c_double,
-        ) -> c_double,
+        cb: extern "C" fn(*mut c_void, T, T, T, T, T, T, T) -> T,
         user_data: *mut c_void,
-    ) -> Pair<bool, *mut c_void>;
-    pub fn symbol_table_free_func7(c_func: *mut c_void);
-
-    pub fn symbol_table_add_func8(
+    ) -> Pair<bool, *mut c_void>,
+    pub symbol_table_free_func7: unsafe extern "C" fn(c_func: *mut c_void),
+    pub symbol_table_add_func8: unsafe extern "C" fn(
         t: *mut CSymbolTable,
         name: *const c_char,
-        cb: extern "C" fn(
-            *mut c_void,
-            c_double,
-            c_double,
-            c_double,
-            c_double,
-            c_double,
-            c_double,
-            c_double,
-            c_double,
-        ) -> c_double,
+        cb: extern "C" fn(*mut c_void, T, T, T, T, T, T, T, T) -> T,
         user_data: *mut c_void,
-    ) -> Pair<bool, *mut c_void>;
-    pub fn symbol_table_free_func8(c_func: *mut c_void);
-
-    pub fn symbol_table_add_func9(
+    ) -> Pair<bool, *mut c_void>,
+    pub symbol_table_free_func8: unsafe extern "C" fn(c_func: *mut c_void),
+    pub symbol_table_add_func9: unsafe extern "C" fn(
         t: *mut CSymbolTable,
         name: *const c_char,
-        cb: extern "C" fn(
-            *mut c_void,
-            c_double,
-            c_double,
-            c_double,
-            c_double,
-            c_double,
-            c_double,
-            c_double,
-            c_double,
-            c_double,
-        ) -> c_double,
+        cb: extern "C" fn(*mut c_void, T, T, T, T, T, T, T, T, T) -> T,
         user_data: *mut c_void,
-    ) -> Pair<bool, *mut c_void>;
-    pub fn symbol_table_free_func9(c_func: *mut c_void);
-
-    pub fn symbol_table_add_func10(
+    ) -> Pair<bool, *mut c_void>,
+    pub symbol_table_free_func9: unsafe extern "C" fn(c_func: *mut c_void),
+    pub symbol_table_add_func10: unsafe extern "C" fn(
         t: *mut CSymbolTable,
         name: *const c_char,
-        cb: extern "C" fn(
-            *mut c_void,
-            c_double,
-            c_double,
-            c_double,
-            c_double,
-            c_double,
-            c_double,
-            c_double,
-            c_double,
-            c_double,
-            c_double,
-        ) -> c_double,
+        cb: extern "C" fn(*mut c_void, T, T, T, T, T, T, T, T, T, T) -> T,
         user_data: *mut c_void,
-    ) -> Pair<bool, *mut c_void>;
-    pub fn symbol_table_free_func10(c_func: *mut c_void);
+    ) -> Pair<bool, *mut c_void>,
+    pub symbol_table_free_func10: unsafe extern "C" fn(c_func: *mut c_void),
+    pub symbol_table_add_polynomial: unsafe extern "C" fn(
+        t: *mut CSymbolTable,
+        name: *const c_char,
+        coeffs: *const T,
+        n: size_t,
+    ) -> Pair<bool, *mut c_void>,
+    pub symbol_table_free_polynomial: unsafe extern "C" fn(p: *mut c_void),
 
     // Expression
-    pub fn expression_new() -> *mut CExpression;
-    pub fn expression_register_symbol_table(e: *mut CExpression, t: *const CSymbolTable);
-    pub fn expression_value(e: *mut CExpression) -> c_double;
-    pub fn expression_destroy(e: *mut CExpression);
-
-    pub fn parser_new() -> *mut CParser;
-    pub fn parser_destroy(p: *mut CParser);
-    pub fn parser_compile(p: *mut CParser, s: *const c_char, e: *const CExpression) -> bool;
-    pub fn parser_compile_resolve(
-        p: *mut CParser,
-        s: *const c_char,
-        e: *const CExpression,
-        cb: extern "C" fn(*const c_char, *mut c_void) -> *const c_char,
-        fn_pointer: *mut c_void,
-    ) -> bool;
-    pub fn parser_error(p: *mut CParser) -> *const CParseError;
+    pub expression_new: unsafe extern "C" fn() -> *mut CExpression,
+    pub expression_destroy: unsafe extern "C" fn(e: *mut CExpression),
+    pub expression_register_symbol_table:
+        unsafe extern "C" fn(e: *mut CExpression, t: *const CSymbolTable),
+    pub expression_value: unsafe extern "C" fn(e: *mut CExpression) -> T,
+    pub expression_eval_batch: unsafe extern "C" fn(
+        e: *mut CExpression,
+        vars: *const *mut T,
+        inputs: *const *const T,
+        strides: *const size_t,
+        n_inputs: size_t,
+        out: *mut T,
+        n_rows: size_t,
+    ),
+    pub expression_derivative:
+        unsafe extern "C" fn(e: *mut CExpression, x: *mut T, h: T, order: c_int) -> T,
+    pub expression_integrate:
+        unsafe extern "C" fn(e: *mut CExpression, x: *mut T, a: T, b: T, steps: size_t) -> T,
+    pub expression_is_constant: unsafe extern "C" fn(e: *mut CExpression) -> bool,
+    pub expression_is_variable: unsafe extern "C" fn(e: *mut CExpression) -> bool,
+    pub expression_is_unary: unsafe extern "C" fn(e: *mut CExpression) -> bool,
+    pub expression_is_binary: unsafe extern "C" fn(e: *mut CExpression) -> bool,
+    pub expression_is_function: unsafe extern "C" fn(e: *mut CExpression) -> bool,
+    pub expression_is_null: unsafe extern "C" fn(e: *mut CExpression) -> bool,
+}
+
+// functions without polymorphism
+extern "C" {
+
+    pub fn exprtk_api_f32() -> *const Api<f32>;
+    pub fn exprtk_api_f64() -> *const Api<f64>;
+    #[cfg(feature = "complex")]
+    pub fn exprtk_api_c64() -> *const Api<num_complex::Complex64>;
+
     pub fn parser_error_free(p: *const CParseError);
 
+    pub fn symbol_list_free(l: *mut CSymbolList);
+
+    pub fn function_list_free(l: *mut CFunctionList);
+
     pub fn string_array_free(l: *mut CStrList);
 
+    pub fn exprtk_reserved_words() -> *mut CStrList;
+    pub fn exprtk_base_functions() -> *mut CStrList;
+    pub fn exprtk_arithmetic_operators() -> *mut CStrList;
+    pub fn exprtk_assignment_operators() -> *mut CStrList;
+    pub fn exprtk_inequality_operators() -> *mut CStrList;
+    pub fn exprtk_logic_operators() -> *mut CStrList;
+
+    pub fn lexer_tokenize(s: *const c_char) -> *mut CTokenList;
+    pub fn token_list_free(l: *mut CTokenList);
+
     pub fn cpp_string_create(s: *const c_char, len: size_t) -> *mut CppString;
     pub fn cpp_string_set(s: *mut CppString, replacement: *const c_char, len: size_t);
     pub fn cpp_string_get(s: *const CppString) -> *const c_char;
+    pub fn cpp_string_swap(s: *mut CppString, other: *mut CppString);
     pub fn cpp_string_free(s: *mut CppString);
 
 }
@@ -0,0 +1,98 @@
+use std::collections::HashSet;
+use std::ffi::CStr;
+use std::sync::OnceLock;
+
+use exprtk_sys::*;
+
+#[cfg(any(feature = "rtl_vecops", feature = "all"))]
+use super::SymbolTable;
+
+fn names_from_list(l: *mut CStrList) -> Vec<String> {
+    unsafe {
+        let out = (*l)
+            .get_slice()
+            .iter()
+            .map(|s| string_from_ptr!(*s))
+            .collect();
+        string_array_free(l);
+        out
+    }
+}
+
+/// Returns the reserved words of ExprTk (e.g. `for`, `var`, `swap` or `like`),
+/// which cannot be used as names of variables or functions. This includes
+/// control statements disabled at build time (`break`, `continue` and `return`).
+pub fn reserved_words() -> Vec<String> {
+    names_from_list(unsafe { exprtk_reserved_words() })
+}
+
+/// Returns the names of the functions built into the ExprTk parser
+/// (e.g. `sin`, `clamp` or `inrange`).
+pub fn base_functions() -> Vec<String> {
+    names_from_list(unsafe { exprtk_base_functions() })
+}
+
+/// Returns the names of the vector functions of the ExprTk runtime library
+/// (e.g. `sort`, `iota` or `dot`), which are added to a symbol table with
+/// `SymbolTable::add_vecops_package()`.
+#[cfg(any(feature = "rtl_vecops", feature = "all"))]
+pub fn vecops_functions() -> Vec<String> {
+    let mut s = SymbolTable::new();
+    s.add_vecops_package();
+    s.function_names()
+}
+
+/// Returns the arithmetic, assignment, inequality and logic operators
+/// (the latter being words such as `and` or `xor`).
+pub fn operators() -> Vec<String> {
+    unsafe {
+        [
+            exprtk_arithmetic_operators(),
+            exprtk_assignment_operators(),
+            exprtk_inequality_operators(),
+            exprtk_logic_operators(),
+        ]
+    }
+    .iter()
+    .flat_map(|&l| names_from_list(l))
+    .collect()
+}
+
+/// Returns `true` if the name is a reserved word or the name of a built-in
+/// function, which would clash with a variable of the same name. Case is
+/// ignored if the `caseinsensitivity` feature is enabled.
+///
+/// # Example:
+/// ```
+/// use exprtk_rs::*;
+///
+/// assert!(is_reserved_name("var"));
+/// assert!(is_reserved_name("sin"));
+/// assert!(!is_reserved_name("x"));
+/// ```
+pub fn is_reserved_name(name: &str) -> bool {
+    if cfg!(any(feature = "caseinsensitivity", feature = "all")) {
+        reserved_names().contains(&name.to_ascii_lowercase())
+    } else {
+        reserved_names().contains(name)
+    }
+}
+
+// Reserved words and built-in functions (lowercase if case is ignored), which
+// are only obtained once
+fn reserved_names() -> &'static HashSet<String> {
+    static NAMES: OnceLock<HashSet<String>> = OnceLock::new();
+    NAMES.get_or_init(|| {
+        reserved_words()
+            .into_iter()
+            .chain(base_functions())
+            .map(|n| {
+                if cfg!(any(feature = "caseinsensitivity", feature = "all")) {
+                    n.to_ascii_lowercase()
+                } else {
+                    n
+                }
+            })
+            .collect()
+    })
+}
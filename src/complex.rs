@@ -0,0 +1,36 @@
+use super::*;
+
+/// `SymbolTable` holding complex valued variables, vectors and functions
+/// (`complex` feature). It is created with `ComplexSymbolTable::default()`.
+///
+/// # Example:
+/// ```
+/// use exprtk_rs::*;
+///
+/// let mut symbol_table = ComplexSymbolTable::default();
+/// symbol_table.add_imaginary_unit();
+/// let s_id = symbol_table.add_variable("s", Complex64::new(0., 1.)).unwrap().unwrap();
+/// symbol_table.add_constant("tau", Complex64::new(2., 0.)).unwrap();
+///
+/// let mut expr = ComplexExpression::new("1 / (1 + s * tau)", symbol_table).unwrap();
+/// assert!((expr.value() - Complex64::new(0.2, -0.4)).norm() < 1e-12);
+///
+/// expr.symbols().value_cell(s_id).set(Complex64::new(0., 0.5));
+/// assert!((expr.value() - Complex64::new(0.5, -0.5)).norm() < 1e-12);
+/// ```
+pub type ComplexSymbolTable = SymbolTable<Complex64>;
+
+/// `Expression` evaluated with complex numbers (`complex` feature).
+///
+/// Numbers in formulas are always real, imaginary values are written as
+/// multiples of the imaginary unit (see `SymbolTable::add_imaginary_unit()`).
+/// Comparison operators only consider the real part, except for equality.
+pub type ComplexExpression = Expression<Complex64>;
+
+impl SymbolTable<Complex64> {
+    /// Adds the imaginary unit as constant `i`. Returns `false` if the name
+    /// was already present.
+    pub fn add_imaginary_unit(&mut self) -> bool {
+        self.add_constant("i", Complex64::new(0., 1.)).unwrap()
+    }
+}
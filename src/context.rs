@@ -0,0 +1,191 @@
+use super::*;
+
+/// Detached copy of the values of all variables, strings, vectors and vector views
+/// of a `SymbolTable`, obtained with `SymbolTable::value_store()` or
+/// `EvaluationContext::new_store()`. The values are accessed with the same
+/// IDs as in the symbol table. The length of vectors cannot be changed, whereas
+/// vector views can hold a different (non-zero) number of elements up to their
+/// capacity.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ValueStore<T: Numeric = c_double> {
+    pub(crate) values: Vec<T>,
+    pub(crate) strings: Vec<StringValue>,
+    pub(crate) vectors: Vec<Box<[T]>>,
+    pub(crate) views: Vec<Vec<T>>,
+}
+
+impl<T: Numeric> ValueStore<T> {
+    /// Returns the value of a variable given its variable ID
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the `var_id` refers to an invalid (too large)
+    /// variable ID.
+    #[inline]
+    pub fn value(&self, var_id: usize) -> T {
+        *self.values.get(var_id).expect("Invalid variable ID")
+    }
+
+    /// Returns a mutable reference to the value of a variable.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the `var_id` refers to an invalid (too large)
+    /// variable ID.
+    #[inline]
+    pub fn value_mut(&mut self, var_id: usize) -> &mut T {
+        self.values.get_mut(var_id).expect("Invalid variable ID")
+    }
+
+    /// Returns a string value given its variable ID.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the `var_id` refers to an invalid (too large)
+    /// variable ID.
+    #[inline]
+    pub fn string(&self, var_id: usize) -> &str {
+        self.strings.get(var_id).expect("Invalid variable ID").get()
+    }
+
+    #[inline]
+    pub fn set_string(&mut self, var_id: usize, text: &str) -> bool {
+        if let Some(s) = self.strings.get_mut(var_id) {
+            s.set(text);
+            return true;
+        }
+        false
+    }
+
+    /// Returns a reference to a vector given its variable ID.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the `var_id` refers to an invalid (too large)
+    /// variable ID.
+    #[inline]
+    pub fn vector(&self, var_id: usize) -> &[T] {
+        self.vectors.get(var_id).expect("Invalid variable ID")
+    }
+
+    /// Returns a mutable reference to a vector given its variable ID.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the `var_id` refers to an invalid (too large)
+    /// variable ID.
+    #[inline]
+    pub fn vector_mut(&mut self, var_id: usize) -> &mut [T] {
+        self.vectors.get_mut(var_id).expect("Invalid variable ID")
+    }
+
+    /// Returns the elements of a vector view given its ID.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the `view_id` refers to an invalid (too large) ID.
+    #[inline]
+    pub fn vector_view(&self, view_id: usize) -> &[T] {
+        self.views.get(view_id).expect("Invalid vector view ID")
+    }
+
+    /// Returns a mutable reference to the buffer of a vector view, which may be
+    /// replaced by a buffer of different length (see `VectorView::rebind()`).
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the `view_id` refers to an invalid (too large) ID.
+    #[inline]
+    pub fn vector_view_mut(&mut self, view_id: usize) -> &mut Vec<T> {
+        self.views.get_mut(view_id).expect("Invalid vector view ID")
+    }
+}
+
+/// A compiled expression, which is evaluated with values from different
+/// `ValueStore` instances. The values are swapped into the symbol table of the
+/// expression without recompiling it, avoiding to set every variable before each
+/// evaluation. Strings and vector views are exchanged without copying their
+/// contents, whereas numeric variables and regular vectors are copied into the
+/// symbol table and back for every evaluation (see `SymbolTable::swap_values()`).
+/// Assignments made by the expression are stored in the respective `ValueStore`.
+///
+/// # Example:
+/// ```
+/// use exprtk_rs::*;
+///
+/// let mut symbol_table = SymbolTable::new();
+/// let a_id = symbol_table.add_variable("a", 0.).unwrap().unwrap();
+/// let v_id = symbol_table.add_vector("v", &[0., 0.]).unwrap().unwrap();
+/// let expr = Expression::new("a * (v[0] + v[1])", symbol_table).unwrap();
+/// let mut context = EvaluationContext::new(expr);
+///
+/// let mut stores: Vec<_> = (1..4)
+///     .map(|i| {
+///         let mut store = context.new_store();
+///         *store.value_mut(a_id) = i as f64;
+///         store.vector_mut(v_id).copy_from_slice(&[1., 2.]);
+///         store
+///     })
+///     .collect();
+///
+/// let results: Vec<_> = stores.iter_mut().map(|s| context.value(s)).collect();
+/// assert_eq!(results, vec![3., 6., 9.]);
+/// ```
+#[derive(Debug)]
+pub struct EvaluationContext<T: Numeric = c_double> {
+    expr: Expression<T>,
+}
+
+impl<T: Numeric> EvaluationContext<T> {
+    pub fn new(expr: Expression<T>) -> EvaluationContext<T> {
+        EvaluationContext { expr }
+    }
+
+    /// Returns a new `ValueStore` initialized with the current values of the
+    /// symbol table.
+    pub fn new_store(&self) -> ValueStore<T> {
+        self.expr.symbols().value_store()
+    }
+
+    /// Evaluates the expression with the values of `store`.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the store does not match the symbol table of
+    /// the expression.
+    pub fn value(&mut self, store: &mut ValueStore<T>) -> T {
+        self.expr.symbols_mut().swap_values(store);
+        let value = self.expr.value();
+        self.expr.symbols_mut().swap_values(store);
+        value
+    }
+
+    /// Exchanges the values in the symbol table with the ones in `store`.
+    /// This is useful for evaluating the expression with the same values multiple
+    /// times using `expression_mut().value()`.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the store does not match the symbol table of
+    /// the expression.
+    pub fn swap(&mut self, store: &mut ValueStore<T>) {
+        self.expr.symbols_mut().swap_values(store);
+    }
+
+    /// Returns a reference to the expression
+    #[inline]
+    pub fn expression(&self) -> &Expression<T> {
+        &self.expr
+    }
+
+    /// Returns a mutable reference to the expression. Stores created before
+    /// adding new symbols cannot be used anymore.
+    #[inline]
+    pub fn expression_mut(&mut self) -> &mut Expression<T> {
+        &mut self.expr
+    }
+
+    pub fn into_expression(self) -> Expression<T> {
+        self.expr
+    }
+}
@@ -4,6 +4,7 @@ use std::fmt;
 
 use enum_primitive::FromPrimitive;
 use exprtk_sys::*;
+use libc::c_double;
 
 pub type ParseResult<T> = Result<T, ParseError>;
 
@@ -32,18 +33,18 @@ impl ParseError {
         }
     }
 
-    pub(super) unsafe fn from_c_err(c_parser: *mut CParser) -> Option<Self> {
-        let e: &CParseError = &*parser_error(c_parser);
+    pub(super) unsafe fn from_c_err<T: Numeric>(c_parser: *mut CParser) -> Option<Self> {
+        let e: &CParseError = &*(T::api().parser_error)(c_parser);
         if e.is_err {
             let err_out = ParseError {
-                kind: ParseErrorKind::from_i32(e.mode as i32)
+                kind: ParseErrorKind::from_i32(e.mode)
                     .unwrap_or_else(|| panic!("Unknown ParseErrorKind enum variant: {}", e.mode)),
                 token_type: string_from_ptr!(e.token_type),
                 token_value: string_from_ptr!(e.token_value),
                 message: string_from_ptr!(e.diagnostic),
                 line: string_from_ptr!(e.error_line),
-                line_no: e.line_no as usize,
-                column_no: e.column_no as usize,
+                line_no: e.line_no,
+                column_no: e.column_no,
             };
             parser_error_free(e as *const CParseError);
             Some(err_out)
@@ -82,7 +83,7 @@ impl fmt::Display for ParseError {
 
 impl Error for ParseError {}
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct InvalidName(pub String);
 
 impl fmt::Display for InvalidName {
@@ -98,3 +99,208 @@ impl From<InvalidName> for ParseError {
         ParseError::simple_syntax(&e.0, "Non-ASCII character or null byte found in formula")
     }
 }
+
+/// Error returned by the solvers (e.g. `Expression::solve_brent()`).
+#[derive(Debug, PartialEq, Clone)]
+pub enum SolverError<T: Real = c_double> {
+    /// The function values at the ends of the interval have the same sign,
+    /// so the interval does not bracket a root.
+    NoBracket { a: T, b: T, fa: T, fb: T },
+    /// The tolerance was not reached within the maximum number of iterations.
+    /// `x` is the last estimate, `fx` the function value at this point.
+    NotConverged { x: T, fx: T, iterations: usize },
+    /// The derivative is zero (Newton's method).
+    ZeroDerivative { x: T },
+    /// The function value or derivative at `x` is infinite or NaN.
+    NotFinite { x: T },
+}
+
+impl<T: Real> fmt::Display for SolverError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SolverError::NoBracket { a, b, fa, fb } => write!(
+                f,
+                "Interval [{}, {}] does not bracket a root (f(a) = {}, f(b) = {})",
+                a, b, fa, fb
+            ),
+            SolverError::NotConverged { x, fx, iterations } => write!(
+                f,
+                "No convergence after {} iterations (x = {}, f(x) = {})",
+                iterations, x, fx
+            ),
+            SolverError::ZeroDerivative { x } => write!(f, "Derivative is zero at x = {}", x),
+            SolverError::NotFinite { x } => write!(f, "Non-finite value at x = {}", x),
+        }
+    }
+}
+
+impl<T: Real> Error for SolverError<T> {}
+
+/// Error returned by `Expression::integrate_simpson()` and `integrate_gauss_kronrod()`.
+#[derive(Debug, PartialEq, Clone)]
+pub enum IntegrationError<T: Real = c_double> {
+    /// The expression evaluated to an infinite value or NaN at `x`.
+    NotFinite { x: T },
+    /// The maximum number of evaluations of `integrate_simpson()` was reached
+    /// before the tolerance, e.g. because the tolerance is too small.
+    TooManyEvaluations { evaluations: usize },
+}
+
+impl<T: Real> fmt::Display for IntegrationError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            IntegrationError::NotFinite { x } => write!(f, "Non-finite value at x = {}", x),
+            IntegrationError::TooManyEvaluations { evaluations } => {
+                write!(f, "Tolerance not reached after {} evaluations", evaluations)
+            }
+        }
+    }
+}
+
+impl<T: Real> Error for IntegrationError<T> {}
+
+/// Error returned by `fit_curve()`.
+#[derive(Debug, PartialEq, Clone)]
+pub enum FitError {
+    /// The formula could not be compiled, or an input name is invalid.
+    Parse(ParseError),
+    /// There are not more observations than parameters.
+    TooFewObservations {
+        observations: usize,
+        parameters: usize,
+    },
+    /// The number of values of an input differs from the number of observations.
+    LengthMismatch {
+        input: String,
+        len: usize,
+        observations: usize,
+    },
+    /// An input variable is already present in the symbol table.
+    InputExists(String),
+    /// An initial value was given for a name that is not a parameter of the formula.
+    UnknownParameter(String),
+    /// The tolerance was not reached within the maximum number of iterations, or
+    /// the sum of squares could not be decreased any more outside of a minimum.
+    NotConverged { iterations: usize },
+    /// The normal equations are singular, e.g. because a parameter has no
+    /// influence on the result.
+    Singular,
+    /// The expression evaluated to an infinite value or NaN.
+    NotFinite,
+}
+
+impl fmt::Display for FitError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FitError::Parse(e) => e.fmt(f),
+            FitError::TooFewObservations {
+                observations,
+                parameters,
+            } => write!(
+                f,
+                "{} observations are not enough to estimate {} parameters",
+                observations, parameters
+            ),
+            FitError::LengthMismatch {
+                input,
+                len,
+                observations,
+            } => write!(
+                f,
+                "Input '{}' has {} values, but there are {} observations",
+                input, len, observations
+            ),
+            FitError::InputExists(name) => {
+                write!(f, "Input variable '{}' is already present", name)
+            }
+            FitError::UnknownParameter(name) => {
+                write!(f, "Initial value given for unknown parameter '{}'", name)
+            }
+            FitError::NotConverged { iterations } => {
+                write!(f, "No convergence after {} iterations", iterations)
+            }
+            FitError::Singular => write!(f, "Singular matrix, parameters cannot be estimated"),
+            FitError::NotFinite => write!(f, "Non-finite value in model"),
+        }
+    }
+}
+
+impl Error for FitError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            FitError::Parse(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<ParseError> for FitError {
+    fn from(e: ParseError) -> Self {
+        FitError::Parse(e)
+    }
+}
+
+impl From<InvalidName> for FitError {
+    fn from(e: InvalidName) -> Self {
+        FitError::Parse(e.into())
+    }
+}
+
+/// Error returned when adding table functions (e.g. `SymbolTable::add_table_func()`).
+#[derive(Debug, PartialEq, Clone)]
+pub enum TableError {
+    InvalidName(InvalidName),
+    /// The number of values does not match the number of points.
+    LengthMismatch {
+        expected: usize,
+        found: usize,
+    },
+    /// The table does not contain enough points for the interpolation method.
+    TooFewPoints {
+        points: usize,
+        required: usize,
+    },
+    /// The point at `index` is not larger than the previous one.
+    NotIncreasing {
+        index: usize,
+    },
+    /// The point or value at `index` is infinite or NaN.
+    NotFinite {
+        index: usize,
+    },
+}
+
+impl fmt::Display for TableError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TableError::InvalidName(e) => e.fmt(f),
+            TableError::LengthMismatch { expected, found } => {
+                write!(f, "Expected {} values, found {}", expected, found)
+            }
+            TableError::TooFewPoints { points, required } => write!(
+                f,
+                "Table has {} points, at least {} are required",
+                points, required
+            ),
+            TableError::NotIncreasing { index } => {
+                write!(f, "Table points not strictly increasing at index {}", index)
+            }
+            TableError::NotFinite { index } => write!(f, "Non-finite value at index {}", index),
+        }
+    }
+}
+
+impl Error for TableError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            TableError::InvalidName(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<InvalidName> for TableError {
+    fn from(e: InvalidName) -> Self {
+        TableError::InvalidName(e)
+    }
+}
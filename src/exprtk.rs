@@ -1,44 +1,54 @@
 use std::cell::Cell;
 use std::ffi::*;
 use std::fmt;
+use std::marker::PhantomData;
 use std::mem;
 use std::ops::Drop;
 use std::ptr;
 
+#[cfg(any(feature = "rtl_io", feature = "all"))]
+use super::output::{new_sink, write_output, IoPackage, Sink};
 use super::*;
+use enum_primitive::FromPrimitive;
 use exprtk_sys::*;
-use libc::{c_char, c_double, c_void, size_t};
-
-// Sending pointers to CExpression and CSymbolTable
-// around should be safe. Calls to methods with non-mutable
-// access to self should have no side-effects
-unsafe impl Send for Expression {}
-unsafe impl Sync for Expression {}
-unsafe impl Send for SymbolTable {}
-unsafe impl Sync for SymbolTable {}
+use libc::{c_char, c_double, c_int, c_void, size_t};
+
+// Sending pointers to CExpression and CSymbolTable to another thread is safe,
+// since the C++ objects are exclusively owned and ExprTk has no thread-local state.
+// Registered closures are required to be `Send`.
+//
+// `Expression` and `SymbolTable` must *not* be `Sync`: `value_cell()` and
+// `vector_of_cells()` allow modifying values through shared references, and
+// even evaluating an expression (which requires `&mut self`) may modify variables
+// that are also accessible via such `Cell` references.
+unsafe impl<T: Numeric> Send for Expression<T> {}
+unsafe impl<T: Numeric> Send for SymbolTable<T> {}
+// Strings and vector views can only be modified with mutable access
 unsafe impl Send for StringValue {}
 unsafe impl Sync for StringValue {}
+unsafe impl<T: Numeric> Send for VectorView<T> {}
+unsafe impl<T: Numeric> Sync for VectorView<T> {}
 
 fn c_string(s: &str) -> Result<CString, InvalidName> {
     CString::new(s).map_err(|_| InvalidName(s.to_string()))
 }
 
 #[derive(Debug)]
-struct Parser(*mut CParser);
+struct Parser<T: Numeric>(*mut CParser, PhantomData<T>);
 
-impl Parser {
-    pub fn new() -> Parser {
-        unsafe { Parser(parser_new()) }
+impl<T: Numeric> Parser<T> {
+    pub fn new() -> Parser<T> {
+        unsafe { Parser((T::api().parser_new)(), PhantomData) }
     }
 
     fn formula_to_cstring(s: &str) -> Result<CString, ParseError> {
         c_string(s).map_err(From::from)
     }
 
-    pub fn compile(&self, string: &str, expr: &Expression) -> Result<(), ParseError> {
+    pub fn compile(&self, string: &str, expr: *mut CExpression) -> Result<(), ParseError> {
         let formula = Self::formula_to_cstring(string)?;
         unsafe {
-            if !parser_compile(self.0, formula.as_ptr(), expr.expr) {
+            if !(T::api().parser_compile)(self.0, formula.as_ptr(), expr) {
                 return Err(self.get_err());
             }
         }
@@ -48,11 +58,11 @@ impl Parser {
     pub fn compile_resolve<F, S>(
         &self,
         string: &str,
-        expr: &mut Expression,
+        expr: &mut Expression<T>,
         mut func: F,
     ) -> Result<(), ParseError>
     where
-        F: FnMut(&str, &mut SymbolTable) -> Result<(), S>,
+        F: FnMut(&str, &mut SymbolTable<T>) -> Result<(), S>,
         S: AsRef<str>,
     {
         let formula = Self::formula_to_cstring(string)?;
@@ -60,11 +70,11 @@ impl Parser {
         let symbols = expr.symbols_mut();
         let mut user_data = (symbols, &mut func);
         unsafe {
-            let r = parser_compile_resolve(
+            let r = (T::api().parser_compile_resolve)(
                 self.0,
                 formula.as_ptr(),
                 expr_ptr,
-                wrapper::<F, S>,
+                wrapper::<T, F, S>,
                 &mut user_data as *const _ as *mut c_void,
             );
             if !r {
@@ -72,19 +82,30 @@ impl Parser {
             }
         };
 
-        extern "C" fn wrapper<F, S>(c_name: *const c_char, user_data: *mut c_void) -> *const c_char
+        extern "C" fn wrapper<T, F, S>(
+            c_name: *const c_char,
+            user_data: *mut c_void,
+        ) -> *const c_char
         where
-            F: FnMut(&str, &mut SymbolTable) -> Result<(), S>,
+            T: Numeric,
+            F: FnMut(&str, &mut SymbolTable<T>) -> Result<(), S>,
             S: AsRef<str>,
         {
             let (ref mut symbols, ref mut opt_f) =
-                unsafe { &mut *(user_data as *mut (&mut SymbolTable, Option<&mut F>)) };
+                unsafe { &mut *(user_data as *mut (&mut SymbolTable<T>, Option<&mut F>)) };
             let name = unsafe { CStr::from_ptr(c_name).to_str().unwrap() };
             opt_f
                 .as_mut()
                 .map(|ref mut f| {
+                    // must not panic, since unwinding into C++ is not possible
                     if let Err(e) = f(name, symbols) {
-                        return CString::new(e.as_ref()).unwrap().into_raw() as *const c_char;
+                        let msg = e.as_ref().replace('\0', "");
+                        return CString::new(msg).unwrap().into_raw() as *const c_char;
+                    }
+                    // The parser would otherwise ask for the same name again and again
+                    if !matches!(symbols.symbol_exists(name), Ok(true)) {
+                        let msg = format!("Symbol '{}' was not registered by the resolver", name);
+                        return CString::new(msg).unwrap().into_raw() as *const c_char;
                     }
                     ptr::null() as *const c_char
                 })
@@ -93,25 +114,150 @@ impl Parser {
         Ok(())
     }
 
+    pub fn collect_assignments(&self) {
+        unsafe { (T::api().parser_collect_assignments)(self.0, true) }
+    }
+
+    pub fn assignment_symbols(&self) -> Vec<(String, SymbolType)> {
+        unsafe {
+            let l = (T::api().parser_assignment_symbols)(self.0);
+            let out = (*l)
+                .get_names()
+                .iter()
+                .zip((*l).get_types())
+                .map(|(n, &t)| {
+                    let ty = SymbolType::from_i32(t)
+                        .unwrap_or_else(|| panic!("Unknown SymbolType enum variant: {}", t));
+                    (string_from_ptr!(*n), ty)
+                })
+                .collect();
+            symbol_list_free(l);
+            out
+        }
+    }
+
+    // Returns the assigned symbols, failing if a constant vector is modified,
+    // which is not prevented by ExprTk itself
+    pub fn checked_assignments(
+        &self,
+        symbols: &SymbolTable<T>,
+    ) -> Result<Vec<(String, SymbolType)>, ParseError> {
+        let assignments = self.assignment_symbols();
+        for (name, ty) in &assignments {
+            let is_vec = *ty == SymbolType::Vector || *ty == SymbolType::VectorElement;
+            if is_vec && symbols.is_constant_vector(name)? {
+                let msg = format!("Cannot assign to constant vector '{}'", name);
+                return Err(ParseError::simple_syntax(name, &msg));
+            }
+        }
+        Ok(assignments)
+    }
+
     fn get_err(&self) -> ParseError {
-        unsafe { ParseError::from_c_err(self.0) }
+        unsafe { ParseError::from_c_err::<T>(self.0) }
             .expect("Compiler notified about error, but there is none.")
     }
 }
 
-impl Drop for Parser {
+impl<T: Numeric> Drop for Parser<T> {
     fn drop(&mut self) {
-        unsafe { parser_destroy(self.0) };
+        unsafe { (T::api().parser_destroy)(self.0) };
+    }
+}
+
+/// Declaration of an unknown symbol returned by the closure supplied to
+/// `Expression::resolve_unknown()`.
+#[derive(Debug, PartialEq, Clone)]
+pub enum Declaration<T: Numeric = c_double> {
+    /// Scalar variable with an initial value
+    Variable(T),
+    /// Scalar constant
+    Constant(T),
+    /// String variable with an initial value
+    String(String),
+    /// Vector variable with initial values
+    Vector(Vec<T>),
+    /// Do not declare the symbol, compilation fails with the given message
+    Reject(String),
+}
+
+impl<T: Numeric> Declaration<T> {
+    // Adds the symbol to the symbol table, returning its type and ID (if not a constant)
+    fn register(
+        self,
+        name: &str,
+        symbols: &mut SymbolTable<T>,
+    ) -> Result<Option<(SymbolType, usize)>, String> {
+        let res = match self {
+            Declaration::Variable(v) => symbols
+                .add_variable(name, v)
+                .map(|id| id.map(|id| (SymbolType::Variable, id))),
+            Declaration::Constant(v) => symbols.add_constant(name, v).map(|_| None),
+            Declaration::String(s) => symbols
+                .add_stringvar(name, &s)
+                .map(|id| id.map(|id| (SymbolType::String, id))),
+            Declaration::Vector(v) => symbols
+                .add_vector(name, &v)
+                .map(|id| id.map(|id| (SymbolType::Vector, id))),
+            Declaration::Reject(msg) => return Err(msg),
+        };
+        res.map_err(|e| e.to_string())
+    }
+}
+
+enum_from_primitive! {
+    /// Type of a symbol collected from an expression during compilation
+    /// (see `Expression::assigned_symbols`).
+    #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+    pub enum SymbolType {
+        Unknown,
+        Variable,
+        Vector,
+        VectorElement,
+        String,
+        Function,
+        LocalVariable,
+        LocalVector,
+        LocalString
+    }
+}
+
+enum_from_primitive! {
+    /// Kind of a function registered in a symbol table (see `SymbolTable::functions`).
+    #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+    pub enum FunctionKind {
+        /// Function with a fixed number of scalar arguments
+        Scalar,
+        /// Function with a variable number of scalar arguments
+        Vararg,
+        /// Function accepting scalars, vectors and strings
+        Generic,
+        /// Function defined by an expression (`exprtk::function_compositor`)
+        Composited
     }
 }
 
-pub struct Expression {
+/// Description of a function registered in a symbol table, as returned by
+/// `SymbolTable::functions()`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct FunctionInfo {
+    pub name: String,
+    /// Number of arguments or `None` if the number is variable
+    pub arity: Option<usize>,
+    pub kind: FunctionKind,
+    /// `true` if the function has no side effects. Calls of pure functions
+    /// with constant arguments are evaluated during compilation.
+    pub is_pure: bool,
+}
+
+pub struct Expression<T: Numeric = c_double> {
     expr: *mut CExpression,
     string: String,
-    symbols: SymbolTable,
+    symbols: SymbolTable<T>,
+    assignments: Vec<(String, SymbolType)>,
 }
 
-impl Expression {
+impl<T: Numeric> Expression<T> {
     /// Compiles a new `Expression`. Missing variables will lead to a
     /// `exprtk::ParseError`.
     ///
@@ -126,15 +272,18 @@ impl Expression {
     /// let mut expr = Expression::new("a + 1", symbol_table).unwrap();
     /// assert_eq!(expr.value(), 3.);
     /// ```
-    pub fn new(string: &str, symbols: SymbolTable) -> Result<Expression, ParseError> {
-        let parser = Parser::new();
-        let e = Expression {
-            expr: unsafe { expression_new() },
+    pub fn new(string: &str, symbols: SymbolTable<T>) -> Result<Expression<T>, ParseError> {
+        let parser = Parser::<T>::new();
+        let mut e = Expression {
+            expr: unsafe { (T::api().expression_new)() },
             string: string.to_string(),
             symbols,
+            assignments: vec![],
         };
         e.register_symbol_table();
-        parser.compile(string, &e)?;
+        parser.collect_assignments();
+        parser.compile(string, e.expr)?;
+        e.assignments = parser.checked_assignments(&e.symbols)?;
         Ok(e)
     }
 
@@ -144,12 +293,12 @@ impl Expression {
     /// with the new `Expression` instance.
     pub fn parse_vars(
         string: &str,
-        symbols: SymbolTable,
-    ) -> Result<(Expression, Vec<(String, usize)>), ParseError> {
+        symbols: SymbolTable<T>,
+    ) -> Result<(Self, Vec<(String, usize)>), ParseError> {
         let mut vars = vec![];
         let e = Expression::handle_unknown(string, symbols, |name, symbols| {
             let var_id = symbols
-                .add_variable(name, 0.)
+                .add_variable(name, T::default())
                 .map_err(|_| "invalid name.")?
                 .unwrap();
             vars.push((name.to_string(), var_id));
@@ -180,33 +329,178 @@ impl Expression {
     ///
     /// assert_eq!(expr.value(), 8.);
     /// ```
-    /// **Note**: this function is very flexible, but can cause problems if **not** registering
-    /// anything for a given name. In that case, the same variable name will be brought up again
-    /// and again, infinite loop, ultimately resulting in a stack overflow.
+    /// **Note**: If the closure returns `Ok(())` without registering anything for a given
+    /// name, compilation fails with a `ParseError`. `Expression::resolve_unknown()` offers
+    /// a simpler interface, which does the registration itself.
     pub fn handle_unknown<F>(
         string: &str,
-        symbols: SymbolTable,
+        symbols: SymbolTable<T>,
         func: F,
-    ) -> Result<Expression, ParseError>
+    ) -> Result<Expression<T>, ParseError>
     where
-        F: FnMut(&str, &mut SymbolTable) -> Result<(), String>,
+        F: FnMut(&str, &mut SymbolTable<T>) -> Result<(), String>,
     {
-        let parser = Parser::new();
+        let parser = Parser::<T>::new();
         let mut e = Expression {
-            expr: unsafe { expression_new() },
+            expr: unsafe { (T::api().expression_new)() },
             string: string.to_string(),
             symbols,
+            assignments: vec![],
         };
         e.register_symbol_table();
 
+        parser.collect_assignments();
         parser.compile_resolve(string, &mut e, func)?;
+        e.assignments = parser.checked_assignments(&e.symbols)?;
 
         Ok(e)
     }
 
+    /// Handles unknown symbols like `Expression::handle_unknown()`, but instead of
+    /// registering the symbols, the closure returns a `Declaration` describing what
+    /// the symbol should be. The symbol is then added to the symbol table by this
+    /// function. `Declaration::Reject` stops compilation with the given error message.
+    /// The names, types and IDs of the added symbols are returned together with the
+    /// new `Expression` (in the order of their appearance). Constants are not listed,
+    /// since they don't have an ID.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use exprtk_rs::*;
+    ///
+    /// let formula = "s_string[] + a + b + v[1]";
+    /// let (mut expr, symbols) = Expression::resolve_unknown(formula, SymbolTable::new(), |name| {
+    ///     match name {
+    ///         "a" => Declaration::Constant(1.),
+    ///         "v" => Declaration::Vector(vec![1., 2.]),
+    ///         n if n.starts_with("s_") => Declaration::String("string".to_string()),
+    ///         _ => Declaration::Variable(1.),
+    ///     }
+    /// }).unwrap();
+    ///
+    /// assert_eq!(expr.value(), 10.);
+    /// assert_eq!(symbols[1], ("b".to_string(), SymbolType::Variable, 0));
+    ///
+    /// let res = Expression::resolve_unknown("a + b", SymbolTable::new(), |name| {
+    ///     Declaration::Reject(format!("'{}' is not allowed", name))
+    /// });
+    /// assert!(res.unwrap_err().message.contains("'a' is not allowed"));
+    /// ```
+    #[allow(clippy::type_complexity)]
+    pub fn resolve_unknown<F>(
+        string: &str,
+        symbols: SymbolTable<T>,
+        mut func: F,
+    ) -> Result<(Expression<T>, Vec<(String, SymbolType, usize)>), ParseError>
+    where
+        F: FnMut(&str) -> Declaration<T>,
+    {
+        let mut resolved = vec![];
+        let e = Expression::handle_unknown(string, symbols, |name, symbols| {
+            if let Some((ty, id)) = func(name).register(name, symbols)? {
+                resolved.push((name.to_string(), ty, id));
+            }
+            Ok(())
+        })?;
+        Ok((e, resolved))
+    }
+
+    /// Compiles a new `Expression`, looking up unknown symbols in a `SymbolProvider`,
+    /// e.g. a `HashMap<String, f64>`. Only the symbols used in the formula are added
+    /// to the symbol table. Their names, types and IDs are returned together with the
+    /// new `Expression` instance (in the order of their appearance). Constants are not
+    /// listed, since they don't have an ID. Names not known by the provider lead to
+    /// a `ParseError`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::collections::HashMap;
+    /// use exprtk_rs::*;
+    ///
+    /// let mut values = HashMap::new();
+    /// values.insert("a".to_string(), 2.);
+    /// values.insert("b".to_string(), 3.);
+    /// values.insert("unused".to_string(), 0.);
+    ///
+    /// let (mut expr, vars) = Expression::parse_provided("a * b", SymbolTable::new(), &values).unwrap();
+    /// assert_eq!(
+    ///     vars,
+    ///     vec![("a".to_string(), SymbolType::Variable, 0), ("b".to_string(), SymbolType::Variable, 1)]
+    /// );
+    /// assert_eq!(expr.value(), 6.);
+    /// assert!(expr.symbols().get_var_id("unused").unwrap().is_none());
+    /// ```
+    #[allow(clippy::type_complexity)]
+    pub fn parse_provided<P>(
+        string: &str,
+        symbols: SymbolTable<T>,
+        mut provider: P,
+    ) -> Result<(Expression<T>, Vec<(String, SymbolType, usize)>), ParseError>
+    where
+        P: SymbolProvider<T>,
+    {
+        Expression::resolve_unknown(string, symbols, |name| {
+            provider
+                .provide(name)
+                .unwrap_or_else(|| Declaration::Reject(format!("Undefined symbol: '{}'", name)))
+        })
+    }
+
+    /// Compiles a new formula, replacing the current one. The symbol table is kept,
+    /// therefore all variable IDs remain valid. If compilation fails, the error is
+    /// returned and the `Expression` still evaluates the previous formula.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use exprtk_rs::*;
+    ///
+    /// let mut symbol_table = SymbolTable::new();
+    /// let x_id = symbol_table.add_variable("x", 2.).unwrap().unwrap();
+    /// let mut expr = Expression::new("x + 1", symbol_table).unwrap();
+    /// assert_eq!(expr.value(), 3.);
+    ///
+    /// expr.recompile("x * 3").unwrap();
+    /// assert_eq!(expr.value(), 6.);
+    ///
+    /// assert!(expr.recompile("x * y").is_err());
+    /// *expr.symbols_mut().value_mut(x_id) = 1.;
+    /// assert_eq!(expr.value(), 3.);
+    /// ```
+    pub fn recompile(&mut self, string: &str) -> Result<(), ParseError> {
+        let parser = Parser::<T>::new();
+        let expr = unsafe { (T::api().expression_new)() };
+        unsafe { (T::api().expression_register_symbol_table)(expr, self.symbols.sym) };
+        parser.collect_assignments();
+        let assignments = parser
+            .compile(string, expr)
+            .and_then(|_| parser.checked_assignments(&self.symbols));
+        match assignments {
+            Ok(a) => {
+                unsafe { (T::api().expression_destroy)(self.expr) };
+                self.expr = expr;
+                self.string = string.to_string();
+                self.assignments = a;
+                Ok(())
+            }
+            Err(e) => {
+                unsafe { (T::api().expression_destroy)(expr) };
+                Err(e)
+            }
+        }
+    }
+
+    /// Destroys the compiled expression and returns the symbol table
+    /// with all its variables (and IDs) for further use.
+    pub fn into_symbols(mut self) -> SymbolTable<T> {
+        mem::take(&mut self.symbols)
+    }
+
     fn register_symbol_table(&self) {
         unsafe {
-            expression_register_symbol_table(self.expr, self.symbols.sym);
+            (T::api().expression_register_symbol_table)(self.expr, self.symbols.sym);
         }
     }
 
@@ -216,30 +510,361 @@ impl Expression {
     /// *Note*: This method requires mutable access to the underlying expression
     /// object, since executing an expression can have side-effects. Variables
     /// in the symbol table of the expression can be changed or added.
-    pub fn value(&mut self) -> c_double {
-        unsafe { expression_value(self.expr) }
+    pub fn value(&mut self) -> T {
+        unsafe { (T::api().expression_value)(self.expr) }
+    }
+
+    /// Calculates the value of the expression and returns it together with the
+    /// text written by `print()` and `println()` during the evaluation, which is
+    /// not passed on to the sink of the IO package (see
+    /// `SymbolTable::add_io_package()`). If the package was not added, the output
+    /// is empty.
+    ///
+    /// # Example:
+    /// ```
+    /// use exprtk_rs::*;
+    ///
+    /// let mut symbol_table = SymbolTable::new();
+    /// symbol_table.add_io_package(std::io::sink());
+    /// let x_id = symbol_table.add_variable("x", 1.).unwrap().unwrap();
+    ///
+    /// let mut expr = Expression::new("print('x: ', x); x + 1", symbol_table).unwrap();
+    /// assert_eq!(expr.value_with_output(), (2., "x:    1.00000".to_string()));
+    /// ```
+    #[cfg(any(feature = "rtl_io", feature = "all"))]
+    pub fn value_with_output(&mut self) -> (T, String) {
+        let buffer = OutputBuffer::new();
+        let prev = self.symbols.replace_io_sink(new_sink(buffer.clone()));
+        let value = self.value();
+        if let Some(prev) = prev {
+            self.symbols.replace_io_sink(prev);
+        }
+        (value, buffer.take())
+    }
+
+    /// Evaluates the expression once for every element of `out`, writing the results
+    /// into it. Before each evaluation, the variables given by their ID are set to the
+    /// next value of the corresponding input slice. The loop runs in C++, avoiding the
+    /// overhead of setting the variables and calling `value()` from Rust for every row.
+    /// After the call, the variables hold the values of the last row.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if a variable ID is invalid, or if an input slice
+    /// is shorter than `out`.
+    ///
+    /// # Example:
+    /// ```
+    /// use exprtk_rs::*;
+    ///
+    /// let mut symbol_table = SymbolTable::new();
+    /// let x_id = symbol_table.add_variable("x", 0.).unwrap().unwrap();
+    /// let y_id = symbol_table.add_variable("y", 0.).unwrap().unwrap();
+    /// let mut expr = Expression::new("x * y", symbol_table).unwrap();
+    ///
+    /// let mut out = [0.; 3];
+    /// expr.eval_batch(&[(x_id, &[1., 2., 3.]), (y_id, &[2., 2., 2.])], &mut out);
+    /// assert_eq!(out, [2., 4., 6.]);
+    /// ```
+    pub fn eval_batch(&mut self, inputs: &[(usize, &[T])], out: &mut [T]) {
+        let inputs: Vec<_> = inputs.iter().map(|&(id, data)| (id, data, 1)).collect();
+        self.eval_batch_strided(&inputs, out)
+    }
+
+    /// Like `eval_batch`, but every input is given as `(var_id, data, stride)`, where the
+    /// value of row `i` is taken from `data[i * stride]`. This allows reading columns
+    /// directly from row-major tables: the column `j` of a table with `n` columns is
+    /// `(var_id, &table[j..], n)`.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if a variable ID is invalid, a stride is zero, or if an
+    /// input slice does not contain enough elements for all rows of `out`.
+    pub fn eval_batch_strided(&mut self, inputs: &[(usize, &[T], usize)], out: &mut [T]) {
+        let n_rows = out.len();
+        let mut vars = Vec::with_capacity(inputs.len());
+        let mut data = Vec::with_capacity(inputs.len());
+        let mut strides = Vec::with_capacity(inputs.len());
+        for &(var_id, values, stride) in inputs {
+            assert!(stride > 0, "Stride must not be zero");
+            if n_rows > 0 {
+                // an overflowing index cannot be within a slice either
+                let last = (n_rows - 1).checked_mul(stride);
+                assert!(
+                    matches!(last, Some(last) if last < values.len()),
+                    "Input of variable {} too short for {} rows",
+                    var_id,
+                    n_rows
+                );
+            }
+            vars.push(
+                *self
+                    .symbols
+                    .values
+                    .get(var_id)
+                    .expect("Invalid variable ID"),
+            );
+            data.push(values.as_ptr());
+            strides.push(stride as size_t);
+        }
+        unsafe {
+            (T::api().expression_eval_batch)(
+                self.expr,
+                vars.as_ptr(),
+                data.as_ptr(),
+                strides.as_ptr(),
+                inputs.len() as size_t,
+                out.as_mut_ptr(),
+                n_rows as size_t,
+            )
+        }
+    }
+
+    /// Returns the numerical derivative of the expression with respect to a variable
+    /// at `x`, using a five-point stencil with step size `h`
+    /// (`exprtk::derivative`). The variable is set to `x` during the calculation
+    /// and restored to its previous value afterwards.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the variable ID is invalid.
+    ///
+    /// # Example:
+    /// ```
+    /// use exprtk_rs::*;
+    ///
+    /// let mut symbol_table = SymbolTable::new();
+    /// let x_id = symbol_table.add_variable("x", 1.).unwrap().unwrap();
+    /// let mut expr = Expression::new("x^3", symbol_table).unwrap();
+    ///
+    /// assert!((expr.derivative(x_id, 2., 1e-3) - 12.).abs() < 1e-6);
+    /// assert_eq!(expr.symbols().value(x_id), 1.);
+    /// ```
+    pub fn derivative(&mut self, var_id: usize, x: T, h: T) -> T {
+        self._derivative(var_id, x, h, 1)
+    }
+
+    /// Returns the numerical second derivative with respect to a variable at `x`
+    /// (`exprtk::second_derivative`), restoring the variable afterwards.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the variable ID is invalid.
+    pub fn second_derivative(&mut self, var_id: usize, x: T, h: T) -> T {
+        self._derivative(var_id, x, h, 2)
+    }
+
+    /// Returns the numerical third derivative with respect to a variable at `x`
+    /// (`exprtk::third_derivative`), restoring the variable afterwards.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the variable ID is invalid.
+    pub fn third_derivative(&mut self, var_id: usize, x: T, h: T) -> T {
+        self._derivative(var_id, x, h, 3)
+    }
+
+    /// Returns the partial derivatives with respect to the given variables at their
+    /// current values (see `derivative()`).
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if a variable ID is invalid.
+    pub fn gradient(&mut self, var_ids: &[usize], h: T) -> Vec<T> {
+        var_ids
+            .iter()
+            .map(|&id| {
+                let x = self.symbols.value(id);
+                self.derivative(id, x, h)
+            })
+            .collect()
+    }
+
+    /// Integrates the expression over a variable from `a` to `b` using Simpson's rule
+    /// with the given number of steps (`exprtk::integrate`). The variable is restored
+    /// to its previous value afterwards. If `a > b`, the result is negative (like
+    /// with the adaptive methods `integrate_simpson()` and `integrate_gauss_kronrod()`,
+    /// which also return an error estimate).
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the variable ID is invalid or `steps` is zero.
+    ///
+    /// # Example:
+    /// ```
+    /// use exprtk_rs::*;
+    ///
+    /// let mut symbol_table = SymbolTable::new();
+    /// let x_id = symbol_table.add_variable("x", 0.).unwrap().unwrap();
+    /// let mut expr = Expression::new("3 * x^2", symbol_table).unwrap();
+    ///
+    /// assert!((expr.integrate(x_id, 0., 2., 100) - 8.).abs() < 1e-10);
+    /// assert!((expr.integrate(x_id, 2., 0., 100) + 8.).abs() < 1e-10);
+    /// assert_eq!(expr.symbols().value(x_id), 0.);
+    /// ```
+    pub fn integrate(&mut self, var_id: usize, a: T, b: T, steps: usize) -> T {
+        assert!(
+            steps > 0,
+            "The number of integration steps must not be zero"
+        );
+        let ptr = *self
+            .symbols
+            .values
+            .get(var_id)
+            .expect("Invalid variable ID");
+        unsafe {
+            // exprtk::integrate() leaves the variable at the last sample point
+            let prev = mem::replace(&mut *ptr, a);
+            let res = (T::api().expression_integrate)(self.expr, ptr, a, b, steps as size_t);
+            *ptr = prev;
+            res
+        }
+    }
+
+    fn _derivative(&mut self, var_id: usize, x: T, h: T, order: c_int) -> T {
+        let ptr = *self
+            .symbols
+            .values
+            .get(var_id)
+            .expect("Invalid variable ID");
+        unsafe {
+            let prev = mem::replace(&mut *ptr, x);
+            let d = (T::api().expression_derivative)(self.expr, ptr, h, order);
+            *ptr = prev;
+            d
+        }
+    }
+
+    /// Returns the formula the expression was compiled from.
+    #[inline]
+    pub fn formula(&self) -> &str {
+        &self.string
+    }
+
+    /// Returns `true` if the compiled expression was reduced to a constant value
+    /// (e.g. `1 + 2` or `pi / 2`). Evaluating it repeatedly will always return the same
+    /// value, regardless of the values in the symbol table.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use exprtk_rs::*;
+    ///
+    /// let mut symbol_table = SymbolTable::new();
+    /// symbol_table.add_variable("x", 1.).unwrap();
+    /// symbol_table.add_constant("c", 2.).unwrap();
+    /// let expr = Expression::new("c * (1 + 2)", symbol_table.clone()).unwrap();
+    /// assert!(expr.is_constant());
+    /// let expr = Expression::new("x", symbol_table).unwrap();
+    /// assert!(!expr.is_constant());
+    /// assert!(expr.is_variable());
+    /// ```
+    pub fn is_constant(&self) -> bool {
+        unsafe { (T::api().expression_is_constant)(self.expr) }
+    }
+
+    /// Returns `true` if the compiled expression consists of a single variable.
+    pub fn is_variable(&self) -> bool {
+        unsafe { (T::api().expression_is_variable)(self.expr) }
+    }
+
+    /// Returns `true` if the root of the compiled expression is a unary operation
+    /// (e.g. `-x`).
+    pub fn is_unary(&self) -> bool {
+        unsafe { (T::api().expression_is_unary)(self.expr) }
+    }
+
+    /// Returns `true` if the root of the compiled expression is a binary operation
+    /// (e.g. `x + y`).
+    pub fn is_binary(&self) -> bool {
+        unsafe { (T::api().expression_is_binary)(self.expr) }
+    }
+
+    /// Returns `true` if the root of the compiled expression is a function call
+    /// (e.g. `sin(x)`).
+    pub fn is_function(&self) -> bool {
+        unsafe { (T::api().expression_is_function)(self.expr) }
+    }
+
+    /// Returns `true` if the compiled expression is a null node, which evaluates to `NaN`
+    /// (e.g. `null` or an empty formula).
+    pub fn is_null(&self) -> bool {
+        unsafe { (T::api().expression_is_null)(self.expr) }
+    }
+
+    /// Returns the names and types of all symbols that are assigned to in the
+    /// expression (e.g. `y := x * 2` or `v[1] += 1`). ExprTk returns the list
+    /// sorted by name (not in the order of appearance), with each symbol listed
+    /// once. Evaluating the expression will modify these values in the
+    /// symbol table. The list is collected at compile time, independently of
+    /// which branches are actually executed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use exprtk_rs::*;
+    ///
+    /// let mut symbol_table = SymbolTable::new();
+    /// symbol_table.add_variable("x", 1.).unwrap();
+    /// symbol_table.add_variable("y", 0.).unwrap();
+    /// symbol_table.add_stringvar("s", "").unwrap();
+    /// let expr = Expression::new("y := x * 2; s := 'text'", symbol_table).unwrap();
+    ///
+    /// assert_eq!(
+    ///     expr.assigned_symbols(),
+    ///     &[("s".to_string(), SymbolType::String), ("y".to_string(), SymbolType::Variable)]
+    /// );
+    /// ```
+    pub fn assigned_symbols(&self) -> &[(String, SymbolType)] {
+        &self.assignments
+    }
+
+    /// Returns the names of the scalar variables assigned to in the expression.
+    pub fn assigned_variables(&self) -> Vec<&str> {
+        self.assigned_of_type(&[SymbolType::Variable])
+    }
+
+    /// Returns the names of the string variables assigned to in the expression.
+    pub fn assigned_strings(&self) -> Vec<&str> {
+        self.assigned_of_type(&[SymbolType::String])
+    }
+
+    /// Returns the names of the vectors assigned to in the expression, either
+    /// as a whole or by element (`v[i] := x`).
+    pub fn assigned_vectors(&self) -> Vec<&str> {
+        self.assigned_of_type(&[SymbolType::Vector, SymbolType::VectorElement])
+    }
+
+    fn assigned_of_type(&self, types: &[SymbolType]) -> Vec<&str> {
+        let mut out: Vec<&str> = vec![];
+        for (name, ty) in &self.assignments {
+            if types.contains(ty) && !out.contains(&name.as_str()) {
+                out.push(name);
+            }
+        }
+        out
     }
 
     /// Returns a reference to the symbol table owned by the `Expression`
     #[inline]
-    pub fn symbols(&self) -> &SymbolTable {
+    pub fn symbols(&self) -> &SymbolTable<T> {
         &self.symbols
     }
 
     /// Returns a mutable reference to the symbol table owned by the `Expression`
     #[inline]
-    pub fn symbols_mut(&mut self) -> &mut SymbolTable {
+    pub fn symbols_mut(&mut self) -> &mut SymbolTable<T> {
         &mut self.symbols
     }
 }
 
-impl Drop for Expression {
+impl<T: Numeric> Drop for Expression<T> {
     fn drop(&mut self) {
-        unsafe { expression_destroy(self.expr) };
+        unsafe { (T::api().expression_destroy)(self.expr) };
     }
 }
 
-impl fmt::Debug for Expression {
+impl<T: Numeric> fmt::Debug for Expression<T> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(
             f,
@@ -249,48 +874,123 @@ impl fmt::Debug for Expression {
     }
 }
 
-impl Clone for Expression {
-    fn clone(&self) -> Expression {
+impl<T: Numeric> Clone for Expression<T> {
+    fn clone(&self) -> Expression<T> {
         Expression::new(&self.string, self.symbols.clone()).unwrap()
     }
 }
 
-struct FuncData {
+/// Returns the Jacobian matrix of several expressions with the same inputs at
+/// `point`. Row `i` contains the partial derivatives of `exprs[i]` with respect
+/// to the variables given by `var_ids` (see `Expression::derivative()`), which
+/// must have the same IDs in all expressions (e.g. if the symbol tables were
+/// cloned from a common one). The variables are set to the values of `point`
+/// during the calculation and restored to their previous values afterwards.
+///
+/// # Panics
+///
+/// This function will panic if a variable ID is invalid, or if `point` and
+/// `var_ids` have different lengths.
+///
+/// # Example:
+/// ```
+/// use exprtk_rs::*;
+///
+/// let mut symbol_table = SymbolTable::new();
+/// let x_id = symbol_table.add_variable("x", 0.).unwrap().unwrap();
+/// let y_id = symbol_table.add_variable("y", 0.).unwrap().unwrap();
+/// let mut exprs = vec![
+///     Expression::new("x * y", symbol_table.clone()).unwrap(),
+///     Expression::new("x + 2 * y", symbol_table).unwrap(),
+/// ];
+///
+/// let jac = jacobian(&mut exprs, &[x_id, y_id], &[3., 4.], 1e-3);
+/// let expected = [[4., 3.], [1., 2.]];
+/// for (row, exp_row) in jac.iter().zip(&expected) {
+///     for (v, e) in row.iter().zip(exp_row) {
+///         assert!((v - e).abs() < 1e-8);
+///     }
+/// }
+/// ```
+pub fn jacobian<T: Numeric>(
+    exprs: &mut [Expression<T>],
+    var_ids: &[usize],
+    point: &[T],
+    h: T,
+) -> Vec<Vec<T>> {
+    assert_eq!(
+        var_ids.len(),
+        point.len(),
+        "Number of variables and values differ"
+    );
+    exprs
+        .iter_mut()
+        .map(|expr| {
+            let prev: Vec<_> = var_ids.iter().map(|&id| expr.symbols.value(id)).collect();
+            for (&id, &x) in var_ids.iter().zip(point) {
+                *expr.symbols.value_mut(id) = x;
+            }
+            let row = expr.gradient(var_ids, h);
+            for (&id, &x) in var_ids.iter().zip(&prev) {
+                *expr.symbols.value_mut(id) = x;
+            }
+            row
+        })
+        .collect()
+}
+
+struct FuncData<T: Numeric> {
     name: String,
     cpp_func: *mut c_void,
     rust_closure: *mut c_void,
-    clone_func: fn(&str, *mut c_void, &mut SymbolTable) -> Result<bool, InvalidName>,
+    clone_func: fn(&str, *mut c_void, &mut SymbolTable<T>) -> Result<bool, InvalidName>,
     free_cpp_func: unsafe extern "C" fn(*mut c_void),
     free_closure_func: fn(*mut c_void),
 }
 
+struct PolyData<T: Numeric> {
+    name: String,
+    coefficients: Vec<T>,
+    cpp_func: *mut c_void,
+}
+
+/// Maximum degree of polynomials added with `SymbolTable::add_polynomial()`
+/// (the limit of `exprtk::polynomial`)
+pub const MAX_POLYNOMIAL_DEGREE: usize = 12;
+
 /// `SymbolTable` holds different variables. There are three types of variables:
 /// Numberic variables, strings and numeric vectors of fixed size. (see
 /// [the documentation](https://github.com/ArashPartow/exprtk/blob/f32d2b4bbb640ea4732b8a7fce1bd9717e9c998b/readme.txt#L643)).
 /// Many but not all of the methods of the [ExprTk symbol_table](http://partow.net/programming/exprtk/doxygen/classexprtk_1_1symbol__table.html)
 /// were implemented, and the API is sometimes different.
-pub struct SymbolTable {
+pub struct SymbolTable<T: Numeric = c_double> {
     sym: *mut CSymbolTable,
-    values: Vec<*mut c_double>,
+    values: Vec<*mut T>,
     strings: Vec<StringValue>,
-    vectors: Vec<Box<[c_double]>>,
-    funcs: Vec<FuncData>,
+    vectors: Vec<Box<[T]>>,
+    views: Vec<VectorView<T>>,
+    const_strings: Vec<StringValue>,
+    const_vectors: Vec<Box<[T]>>,
+    funcs: Vec<FuncData<T>>,
+    polynomials: Vec<PolyData<T>>,
+    #[cfg(any(feature = "rtl_vecops", feature = "all"))]
+    vecops: bool,
+    #[cfg(any(feature = "rtl_io", feature = "all"))]
+    io: Option<Box<IoPackage>>,
 }
 
 impl SymbolTable {
+    /// Creates a new symbol table with double precision. Use
+    /// `SymbolTable::<f32>::default()` for a single precision symbol table.
     pub fn new() -> SymbolTable {
-        SymbolTable {
-            sym: unsafe { symbol_table_new() },
-            values: vec![],
-            strings: vec![],
-            vectors: vec![],
-            funcs: vec![],
-        }
+        Self::default()
     }
+}
 
-    pub fn add_constant(&mut self, name: &str, value: c_double) -> Result<bool, InvalidName> {
+impl<T: Numeric> SymbolTable<T> {
+    pub fn add_constant(&mut self, name: &str, value: T) -> Result<bool, InvalidName> {
         let c_name = c_string(name)?;
-        let rv = unsafe { symbol_table_add_constant(self.sym, c_name.as_ptr(), value) };
+        let rv = unsafe { (T::api().symbol_table_add_constant)(self.sym, c_name.as_ptr(), value) };
         let added = self.validate_added(name, rv, ())?;
         Ok(added.is_some())
     }
@@ -300,24 +1000,20 @@ impl SymbolTable {
     /// The behavior of this function differs from
     /// [the one of the underlying library](http://www.partow.net/programming/exprtk/doxygen/classexprtk_1_1symbol__table.html)
     /// by not providing the (optional) `is_constant` option. Use `add_constant()` instead.
-    pub fn add_variable(
-        &mut self,
-        name: &str,
-        value: c_double,
-    ) -> Result<Option<usize>, InvalidName> {
+    pub fn add_variable(&mut self, name: &str, value: T) -> Result<Option<usize>, InvalidName> {
         let var_id = self.values.len();
         let c_name = c_string(name)?;
         let rv =
-            unsafe { symbol_table_create_variable(self.sym, c_name.as_ptr(), value as c_double) };
+            unsafe { (T::api().symbol_table_create_variable)(self.sym, c_name.as_ptr(), value) };
         let res = self.validate_added(name, rv, var_id)?;
-        let ptr = unsafe { symbol_table_variable_ref(self.sym, c_name.as_ptr()) };
+        let ptr = unsafe { (T::api().symbol_table_variable_ref)(self.sym, c_name.as_ptr()) };
         self.values.push(ptr);
         Ok(res)
     }
 
     #[allow(clippy::mut_from_ref)]
     #[inline]
-    unsafe fn _value_mut(&self, var_id: usize) -> &mut c_double {
+    unsafe fn _value_mut(&self, var_id: usize) -> &mut T {
         let ptr = self.values.get(var_id).expect("Invalid variable ID");
         ptr.as_mut().expect("null pointer!")
     }
@@ -329,7 +1025,7 @@ impl SymbolTable {
     /// This function will panic if the `var_id` refers to an invalid (too large)
     /// variable ID.
     #[inline]
-    pub fn value(&self, var_id: usize) -> c_double {
+    pub fn value(&self, var_id: usize) -> T {
         unsafe { *self._value_mut(var_id) }
     }
 
@@ -357,7 +1053,7 @@ impl SymbolTable {
     /// }
     /// ```
     #[inline]
-    pub fn value_mut(&mut self, var_id: usize) -> &mut c_double {
+    pub fn value_mut(&mut self, var_id: usize) -> &mut T {
         unsafe { self._value_mut(var_id) }
     }
 
@@ -385,7 +1081,7 @@ impl SymbolTable {
     /// assert_eq!(expr.value(), 3.);
     /// ```
     #[inline]
-    pub fn value_cell(&self, var_id: usize) -> &Cell<c_double> {
+    pub fn value_cell(&self, var_id: usize) -> &Cell<T> {
         let mut_ref = unsafe { self._value_mut(var_id) };
         Cell::from_mut(mut_ref)
     }
@@ -396,10 +1092,10 @@ impl SymbolTable {
     ///
     /// This function will panic if the `name` refers to an unknown variable.
     #[inline]
-    pub fn value_from_name(&self, name: &str) -> Result<c_double, InvalidName> {
+    pub fn value_from_name(&self, name: &str) -> Result<T, InvalidName> {
         let c_name = c_string(name)?;
         let var_ref = unsafe {
-            symbol_table_variable_ref(self.sym, c_name.as_ptr())
+            (T::api().symbol_table_variable_ref)(self.sym, c_name.as_ptr())
                 .as_ref()
                 .cloned()
         };
@@ -415,7 +1111,12 @@ impl SymbolTable {
 
         let c_name = c_string(name)?;
         let rv = unsafe {
-            symbol_table_add_stringvar(self.sym, c_name.as_ptr(), self.strings[i].0, false)
+            (T::api().symbol_table_add_stringvar)(
+                self.sym,
+                c_name.as_ptr(),
+                self.strings[i].0,
+                false,
+            )
         };
 
         let res = self.validate_added(name, rv, i);
@@ -425,6 +1126,21 @@ impl SymbolTable {
         res
     }
 
+    /// Adds a string constant, which cannot be modified, neither from Rust nor by
+    /// assignments in an expression. Returns `true` if the string was added /
+    /// `false` if the name was already present.
+    pub fn add_string_constant(&mut self, name: &str, text: &str) -> Result<bool, InvalidName> {
+        let s = StringValue::new(text);
+        let c_name = c_string(name)?;
+        let rv =
+            unsafe { (T::api().symbol_table_add_stringvar)(self.sym, c_name.as_ptr(), s.0, true) };
+        let added = self.validate_added(name, rv, ())?.is_some();
+        if added {
+            self.const_strings.push(s);
+        }
+        Ok(added)
+    }
+
     #[inline]
     pub fn set_string(&mut self, var_id: usize, text: &str) -> bool {
         if let Some(s) = self.strings.get_mut(var_id) {
@@ -458,18 +1174,19 @@ impl SymbolTable {
 
     /// Adds a new vector variable. Returns the variable ID that can later be used for `vector`
     /// or `None` if a variable with the same name was already present.
-    pub fn add_vector(
-        &mut self,
-        name: &str,
-        vec: &[c_double],
-    ) -> Result<Option<usize>, InvalidName> {
+    pub fn add_vector(&mut self, name: &str, vec: &[T]) -> Result<Option<usize>, InvalidName> {
         let i = self.vectors.len();
         let l = vec.len();
         self.vectors.push(vec.to_vec().into_boxed_slice());
 
         let c_name = c_string(name)?;
         let rv = unsafe {
-            symbol_table_add_vector(self.sym, c_name.as_ptr(), self.vectors[i].as_ptr(), l)
+            (T::api().symbol_table_add_vector)(
+                self.sym,
+                c_name.as_ptr(),
+                self.vectors[i].as_ptr(),
+                l,
+            )
         };
 
         let res = self.validate_added(name, rv, i);
@@ -486,8 +1203,8 @@ impl SymbolTable {
     /// This function will panic if the `var_id` refers to an invalid (too large)
     /// variable ID.
     #[inline]
-    pub fn vector(&self, var_id: usize) -> &[c_double] {
-        &**self.vectors.get(var_id).expect("Invalid variable ID")
+    pub fn vector(&self, var_id: usize) -> &[T] {
+        self.vectors.get(var_id).expect("Invalid variable ID")
     }
 
     /// Returns an mutable reference to a vector given its variable ID.
@@ -497,14 +1214,14 @@ impl SymbolTable {
     /// This function will panic if the `var_id` refers to an invalid (too large)
     /// variable ID.
     #[inline]
-    pub fn vector_mut(&mut self, var_id: usize) -> &mut [c_double] {
-        &mut **self.vectors.get_mut(var_id).expect("Invalid variable ID")
+    pub fn vector_mut(&mut self, var_id: usize) -> &mut [T] {
+        self.vectors.get_mut(var_id).expect("Invalid variable ID")
     }
 
     /// Returns a reference to a vector given its variable ID. The values are of the type
     /// `std::cell::Cell`, and can thus be modified without mutable access to `SymbolTable`
     #[inline]
-    pub fn vector_of_cells(&self, var_id: usize) -> &[Cell<c_double>] {
+    pub fn vector_of_cells(&self, var_id: usize) -> &[Cell<T>] {
         let v = self.vector(var_id);
         // Code equivalent to Cell::from_mut(v).as_slice_of_cells(), could be used, but
         // would require mutable access to self.vectors, which we don't have here.
@@ -512,11 +1229,174 @@ impl SymbolTable {
         // SymbolTable::value_vector would then require unsafe code to convert it back.
         // Therefore, using unsafe code here
         unsafe {
-            let cell_slice = &*(v as *const [c_double] as *const Cell<[c_double]>);
+            let cell_slice = &*(v as *const [T] as *const Cell<[T]>);
             cell_slice.as_slice_of_cells()
         }
     }
 
+    /// Adds a vector constant, which cannot be modified from Rust. ExprTk itself has no
+    /// notion of constant vectors, therefore compiling expressions assigning to the
+    /// vector or one of its elements will fail with an error. Other writes are *not*
+    /// prevented: the vector can still be modified by `swap()` and `<=>`, or by
+    /// functions such as `sort()` of the vecops package. Unlike constant variables
+    /// and strings, the elements are not substituted during compilation (no
+    /// constant folding). Returns `true` if the vector was added / `false` if the
+    /// name was already present.
+    ///
+    /// # Example:
+    /// ```
+    /// use exprtk_rs::*;
+    ///
+    /// let mut symbol_table = SymbolTable::new();
+    /// symbol_table.add_vector_constant("table", &[1., 2., 3.]).unwrap();
+    ///
+    /// assert!(Expression::new("table[1] := 0", symbol_table.clone()).is_err());
+    ///
+    /// let mut expr = Expression::new("table[1] * 2", symbol_table).unwrap();
+    /// assert_eq!(expr.value(), 4.);
+    /// ```
+    pub fn add_vector_constant(&mut self, name: &str, vec: &[T]) -> Result<bool, InvalidName> {
+        let v = vec.to_vec().into_boxed_slice();
+        let c_name = c_string(name)?;
+        let rv = unsafe {
+            (T::api().symbol_table_add_vector)(self.sym, c_name.as_ptr(), v.as_ptr(), v.len())
+        };
+        let added = self.validate_added(name, rv, ())?.is_some();
+        if added {
+            self.const_vectors.push(v);
+        }
+        Ok(added)
+    }
+
+    /// Returns `true` if the vector was added with `add_vector_constant()`.
+    pub fn is_constant_vector(&self, name: &str) -> Result<bool, InvalidName> {
+        let ptr = self.get_vec_ptr_from_name(name)?;
+        Ok(!ptr.is_null() && self.const_vectors.iter().any(|v| v.as_ptr() == ptr))
+    }
+
+    /// Adds a vector, whose length and data can be changed after compiling an expression
+    /// (see `VectorView`). The vector is initialized with `capacity` zeroes, the length
+    /// can later be reduced, but never exceed `capacity`. Returns the ID of the
+    /// vector view or `None` if a variable with the same name was already present.
+    /// Vector views have their own IDs, independent of regular vectors.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if `capacity` is zero.
+    ///
+    /// # Example:
+    /// ```
+    /// use exprtk_rs::*;
+    ///
+    /// let mut symbol_table = SymbolTable::new();
+    /// let id = symbol_table.add_vector_view("v", 4).unwrap().unwrap();
+    /// symbol_table.vector_view_mut(id).as_mut_slice().copy_from_slice(&[1., 2., 3., 4.]);
+    ///
+    /// let mut expr = Expression::new("sum(v)", symbol_table).unwrap();
+    /// assert_eq!(expr.value(), 10.);
+    ///
+    /// expr.symbols_mut().vector_view_mut(id).set_len(2);
+    /// assert_eq!(expr.value(), 3.);
+    ///
+    /// let previous = expr.symbols_mut().vector_view_mut(id).rebind(vec![5., 6., 7.]);
+    /// assert_eq!(previous, vec![1., 2., 3., 4.]);
+    /// assert_eq!(expr.value(), 18.);
+    /// ```
+    pub fn add_vector_view(
+        &mut self,
+        name: &str,
+        capacity: usize,
+    ) -> Result<Option<usize>, InvalidName> {
+        assert!(
+            capacity > 0,
+            "The capacity of a vector view must not be zero"
+        );
+        let i = self.views.len();
+        let c_name = c_string(name)?;
+        let view = VectorView::new(vec![T::default(); capacity]);
+        let rv = unsafe {
+            (T::api().symbol_table_add_vector_view)(self.sym, c_name.as_ptr(), view.view)
+        };
+        let res = self.validate_added(name, rv, i);
+        if let Ok(Some(_)) = res {
+            self.views.push(view);
+        }
+        res
+    }
+
+    /// Returns a reference to a vector view given its ID.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the `view_id` refers to an invalid (too large) ID.
+    #[inline]
+    pub fn vector_view(&self, view_id: usize) -> &VectorView<T> {
+        self.views.get(view_id).expect("Invalid vector view ID")
+    }
+
+    /// Returns a mutable reference to a vector view given its ID.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the `view_id` refers to an invalid (too large) ID.
+    #[inline]
+    pub fn vector_view_mut(&mut self, view_id: usize) -> &mut VectorView<T> {
+        self.views.get_mut(view_id).expect("Invalid vector view ID")
+    }
+
+    /// Returns a `ValueStore` holding a copy of the current values of all variables,
+    /// strings, vectors and vector views, which are accessed with the same IDs.
+    pub fn value_store(&self) -> ValueStore<T> {
+        ValueStore {
+            values: (0..self.values.len()).map(|i| self.value(i)).collect(),
+            strings: self.strings.clone(),
+            vectors: self.vectors.clone(),
+            views: self.views.iter().map(|v| v.as_slice().to_vec()).collect(),
+        }
+    }
+
+    /// Exchanges the values of all variables, strings, vectors and vector views with
+    /// the ones in `store`. Compiled expressions are not affected, they will use the
+    /// new values in the next evaluation.
+    ///
+    /// Strings are exchanged without copying their contents, and vector views are
+    /// rebound to the buffers of the store (see `VectorView::rebind()`). Numeric
+    /// variables and the elements of regular vectors are copied, since ExprTk refers
+    /// to their memory locations. Large vectors should therefore be added with
+    /// `add_vector_view()`, if they are exchanged often.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the store was not obtained from this symbol table
+    /// (or a clone of it), if symbols were added since, or if a vector view of the
+    /// store is empty or exceeds the capacity of the view.
+    pub fn swap_values(&mut self, store: &mut ValueStore<T>) {
+        assert!(
+            store.values.len() == self.values.len()
+                && store.strings.len() == self.strings.len()
+                && store.vectors.len() == self.vectors.len()
+                && store.views.len() == self.views.len()
+                && store
+                    .vectors
+                    .iter()
+                    .zip(&self.vectors)
+                    .all(|(v1, v2)| v1.len() == v2.len()),
+            "ValueStore does not match the symbols of the SymbolTable"
+        );
+        for (&ptr, v) in self.values.iter().zip(&mut store.values) {
+            unsafe { ptr::swap(ptr, v) };
+        }
+        for (s, other) in self.strings.iter_mut().zip(&mut store.strings) {
+            s.swap(other);
+        }
+        for (v, other) in self.vectors.iter_mut().zip(&mut store.vectors) {
+            v.swap_with_slice(other);
+        }
+        for (v, other) in self.views.iter_mut().zip(&mut store.views) {
+            *other = v.rebind(mem::take(other));
+        }
+    }
+
     // Validate result of adding variable / string /...
     // add_variable() does three checks, and any of them failing leads to `false`.
     // 1. symbol table sanity
@@ -530,7 +1410,7 @@ impl SymbolTable {
         out: O,
     ) -> Result<Option<O>, InvalidName> {
         if !result {
-            let valid = unsafe { symbol_table_valid(self.sym) };
+            let valid = unsafe { (T::api().symbol_table_valid)(self.sym) };
             if !valid {
                 panic!("Bug: SymbolTable state invalid!");
             }
@@ -546,13 +1426,39 @@ impl SymbolTable {
         Ok(Some(out))
     }
 
-    fn get_var_ptr_from_name(&self, name: &str) -> Result<Option<*mut c_double>, InvalidName> {
+    fn get_var_ptr_from_name(&self, name: &str) -> Result<Option<*mut T>, InvalidName> {
         let c_name = c_string(name)?;
-        let rv = unsafe { symbol_table_variable_ref(self.sym, c_name.as_ptr()) };
+        let rv = unsafe { (T::api().symbol_table_variable_ref)(self.sym, c_name.as_ptr()) };
         let rv = if rv.is_null() { None } else { Some(rv) };
         Ok(rv)
     }
 
+    fn get_vec_ptr_from_name(&self, name: &str) -> Result<*const T, InvalidName> {
+        let c_name = c_string(name)?;
+        Ok(unsafe { (T::api().symbol_table_vector_ptr)(self.sym, c_name.as_ptr()) })
+    }
+
+    // Returns the value of any string (variable or constant)
+    fn string_from_name(&self, name: &str) -> Option<&StringValue> {
+        let c_name = c_string(name).ok()?;
+        let ptr = unsafe { (T::api().symbol_table_stringvar_ref)(self.sym, c_name.as_ptr()) };
+        self.strings
+            .iter()
+            .chain(&self.const_strings)
+            .find(|s| s.0 == ptr)
+    }
+
+    // Returns the values of any vector (variable, constant or view)
+    fn vector_from_name(&self, name: &str) -> Option<&[T]> {
+        let ptr = self.get_vec_ptr_from_name(name).ok()?;
+        self.vectors
+            .iter()
+            .chain(&self.const_vectors)
+            .map(|v| &**v)
+            .chain(self.views.iter().map(|v| v.as_slice()))
+            .find(|v| v.as_ptr() == ptr)
+    }
+
     /// Returns the 'ID' of a variable or None if not found.
     /// The function will return `Err(InvalidName)` if the name is not entirely
     /// composed of ASCII characters.
@@ -566,7 +1472,7 @@ impl SymbolTable {
     /// composed of ASCII characters.
     pub fn get_string_id(&self, name: &str) -> Result<Option<usize>, InvalidName> {
         let c_name = c_string(name)?;
-        let ptr = unsafe { symbol_table_stringvar_ref(self.sym, c_name.as_ptr()) };
+        let ptr = unsafe { (T::api().symbol_table_stringvar_ref)(self.sym, c_name.as_ptr()) };
         let rv = if ptr.is_null() {
             None
         } else {
@@ -579,8 +1485,7 @@ impl SymbolTable {
     /// The function will return `Err(InvalidName)` if the name is not entirely
     /// composed of ASCII characters.
     pub fn get_vec_id(&self, name: &str) -> Result<Option<usize>, InvalidName> {
-        let c_name = c_string(name)?;
-        let ptr = unsafe { symbol_table_vector_ptr(self.sym, c_name.as_ptr()) };
+        let ptr = self.get_vec_ptr_from_name(name)?;
         let rv = if ptr.is_null() {
             None
         } else {
@@ -589,64 +1494,198 @@ impl SymbolTable {
         Ok(rv)
     }
 
+    /// Returns the ID of a vector view or None if not found.
+    /// The function will return `Err(InvalidName)` if the name is not entirely
+    /// composed of ASCII characters.
+    pub fn get_vector_view_id(&self, name: &str) -> Result<Option<usize>, InvalidName> {
+        let ptr = self.get_vec_ptr_from_name(name)?;
+        let rv = if ptr.is_null() {
+            None
+        } else {
+            self.views.iter().position(|v| v.data.as_ptr() == ptr)
+        };
+        Ok(rv)
+    }
+
     pub fn clear_variables(&mut self) {
         self.values.clear();
-        unsafe { symbol_table_clear_variables(self.sym) }
+        unsafe { (T::api().symbol_table_clear_variables)(self.sym) }
     }
 
     pub fn clear_strings(&mut self) {
         self.strings.clear();
-        unsafe { symbol_table_clear_strings(self.sym) }
+        unsafe { (T::api().symbol_table_clear_strings)(self.sym) }
+        self.const_strings.clear();
     }
 
     pub fn clear_vectors(&mut self) {
         self.vectors.clear();
-        unsafe { symbol_table_clear_vectors(self.sym) }
+        unsafe { (T::api().symbol_table_clear_vectors)(self.sym) }
+        // views must be destroyed after removing them from the symbol table
+        self.views.clear();
+        self.const_vectors.clear();
     }
 
     pub fn clear_local_constants(&mut self) {
-        unsafe { symbol_table_clear_local_constants(self.sym) }
+        unsafe { (T::api().symbol_table_clear_local_constants)(self.sym) }
     }
 
     pub fn clear_functions(&mut self) {
-        unsafe { symbol_table_clear_functions(self.sym) }
+        unsafe { (T::api().symbol_table_clear_functions)(self.sym) }
     }
 
     pub fn variable_count(&self) -> usize {
-        unsafe { symbol_table_variable_count(self.sym) as usize }
+        unsafe { (T::api().symbol_table_variable_count)(self.sym) }
     }
 
     pub fn stringvar_count(&self) -> usize {
-        unsafe { symbol_table_stringvar_count(self.sym) as usize }
+        unsafe { (T::api().symbol_table_stringvar_count)(self.sym) }
     }
 
     pub fn vector_count(&self) -> usize {
-        unsafe { symbol_table_vector_count(self.sym) as usize }
+        unsafe { (T::api().symbol_table_vector_count)(self.sym) }
     }
 
     pub fn function_count(&self) -> usize {
-        unsafe { symbol_table_function_count(self.sym) as usize }
+        unsafe { (T::api().symbol_table_function_count)(self.sym) }
     }
 
     pub fn add_constants(&mut self) -> bool {
-        unsafe { symbol_table_add_constants(self.sym) }
+        unsafe { (T::api().symbol_table_add_constants)(self.sym) }
     }
 
     pub fn add_pi(&mut self) -> bool {
-        unsafe { symbol_table_add_pi(self.sym) }
+        unsafe { (T::api().symbol_table_add_pi)(self.sym) }
     }
 
     pub fn add_epsilon(&mut self) -> bool {
-        unsafe { symbol_table_add_epsilon(self.sym) }
+        unsafe { (T::api().symbol_table_add_epsilon)(self.sym) }
     }
 
     pub fn add_infinity(&mut self) -> bool {
-        unsafe { symbol_table_add_infinity(self.sym) }
+        unsafe { (T::api().symbol_table_add_infinity)(self.sym) }
+    }
+
+    /// Adds the vector functions of the ExprTk runtime library (`sort`, `iota`,
+    /// `dot`, etc., see `vecops_functions()`). Returns `false` if the package could
+    /// not be added, which is the case for complex numbers.
+    #[cfg(any(feature = "rtl_vecops", feature = "all"))]
+    pub fn add_vecops_package(&mut self) -> bool {
+        let added = unsafe { (T::api().symbol_table_add_vecops_package)(self.sym) };
+        self.vecops |= added;
+        added
+    }
+
+    /// Adds `print()` and `println()` from the IO package of the ExprTk runtime
+    /// library (`exprtk::rtl::io`), writing to the given sink instead of stdout.
+    /// Scalars are formatted like `printf("%10.5f")` and vector elements are
+    /// separated by spaces, as in ExprTk. Write errors are ignored. Clones of the
+    /// symbol table share the sink. Returns `false` if `print` or `println` are
+    /// already present, or for complex numbers, which are not supported.
+    ///
+    /// Use `OutputBuffer` for collecting the output in memory, or
+    /// `Expression::value_with_output()` for capturing the output of a single
+    /// evaluation.
+    #[cfg(any(feature = "rtl_io", feature = "all"))]
+    pub fn add_io_package<W>(&mut self, sink: W) -> bool
+    where
+        W: std::io::Write + Send + 'static,
+    {
+        self.add_io_sink(new_sink(sink))
+    }
+
+    /// Replaces the sink of the IO package, which also works after expressions
+    /// using this symbol table were compiled. Returns `false` if the package was
+    /// not added with `add_io_package()`.
+    #[cfg(any(feature = "rtl_io", feature = "all"))]
+    pub fn set_io_sink<W>(&mut self, sink: W) -> bool
+    where
+        W: std::io::Write + Send + 'static,
+    {
+        self.replace_io_sink(new_sink(sink)).is_some()
+    }
+
+    #[cfg(any(feature = "rtl_io", feature = "all"))]
+    fn add_io_sink(&mut self, sink: Sink) -> bool {
+        if self.io.is_some() {
+            return false;
+        }
+        let mut io = Box::new(IoPackage {
+            sink,
+            cpp_package: ptr::null_mut(),
+        });
+        io.cpp_package = unsafe {
+            (T::api().symbol_table_add_io_package)(
+                self.sym,
+                write_output,
+                &mut *io as *mut IoPackage as *mut c_void,
+            )
+        };
+        if io.cpp_package.is_null() {
+            return false;
+        }
+        self.io = Some(io);
+        true
+    }
+
+    // Returns the previous sink, or `None` if there is no IO package
+    #[cfg(any(feature = "rtl_io", feature = "all"))]
+    fn replace_io_sink(&mut self, sink: Sink) -> Option<Sink> {
+        self.io.as_mut().map(|io| mem::replace(&mut io.sink, sink))
+    }
+
+    /// Adds a polynomial function of one argument with the given coefficients,
+    /// ordered from the highest degree to the constant term as in ExprTk. The
+    /// function is evaluated natively using `exprtk::polynomial` (Horner's method).
+    /// Returns `true` if the function was added / `false` if the name was already
+    /// present.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if no coefficients are given or if the degree is
+    /// larger than `MAX_POLYNOMIAL_DEGREE`.
+    ///
+    /// # Example:
+    /// ```
+    /// use exprtk_rs::*;
+    ///
+    /// let mut symbol_table = SymbolTable::new();
+    /// // x^2 - 3x + 2
+    /// symbol_table.add_polynomial("p", &[1., -3., 2.]).unwrap();
+    ///
+    /// let mut expr = Expression::new("p(1) + p(4)", symbol_table).unwrap();
+    /// assert_eq!(expr.value(), 0. + 6.);
+    /// ```
+    pub fn add_polynomial(&mut self, name: &str, coefficients: &[T]) -> Result<bool, InvalidName> {
+        assert!(
+            !coefficients.is_empty() && coefficients.len() <= MAX_POLYNOMIAL_DEGREE + 1,
+            "Polynomials must have 1 to {} coefficients, found {}",
+            MAX_POLYNOMIAL_DEGREE + 1,
+            coefficients.len()
+        );
+        let c_name = c_string(name)?;
+        let result = unsafe {
+            (T::api().symbol_table_add_polynomial)(
+                self.sym,
+                c_name.as_ptr(),
+                coefficients.as_ptr(),
+                coefficients.len(),
+            )
+        };
+        let is_new = self.validate_added(name, result.0, ())?.is_some();
+        if is_new {
+            self.polynomials.push(PolyData {
+                name: name.to_string(),
+                coefficients: coefficients.to_vec(),
+                cpp_func: result.1,
+            });
+        }
+        Ok(is_new)
     }
 
     pub fn get_variable_names(&self) -> Vec<String> {
         unsafe {
-            let l = symbol_table_get_variable_list(self.sym);
+            let l = (T::api().symbol_table_get_variable_list)(self.sym);
             let out = (*l)
                 .get_slice()
                 .iter()
@@ -659,7 +1698,7 @@ impl SymbolTable {
 
     pub fn get_stringvar_names(&self) -> Vec<String> {
         unsafe {
-            let l = symbol_table_get_stringvar_list(self.sym);
+            let l = (T::api().symbol_table_get_stringvar_list)(self.sym);
             let out = (*l)
                 .get_slice()
                 .iter()
@@ -672,7 +1711,7 @@ impl SymbolTable {
 
     pub fn get_vector_names(&self) -> Vec<String> {
         unsafe {
-            let l = symbol_table_get_vector_list(self.sym);
+            let l = (T::api().symbol_table_get_vector_list)(self.sym);
             let out = (*l)
                 .get_slice()
                 .iter()
@@ -683,21 +1722,72 @@ impl SymbolTable {
         }
     }
 
+    /// Returns the names of all functions. Functions added with `add_func1()` etc.
+    /// are included, as well as any functions registered in C++ (e.g. when loading
+    /// a symbol table).
+    pub fn function_names(&self) -> Vec<String> {
+        self.functions().into_iter().map(|f| f.name).collect()
+    }
+
+    /// Returns the name, number of arguments, kind and purity of all registered
+    /// functions.
+    ///
+    /// # Example:
+    /// ```
+    /// use exprtk_rs::*;
+    ///
+    /// let mut symbol_table = SymbolTable::new();
+    /// symbol_table.add_func2("add", |x, y| x + y).unwrap();
+    ///
+    /// let info = &symbol_table.functions()[0];
+    /// assert_eq!(info.name, "add");
+    /// assert_eq!(info.arity, Some(2));
+    /// assert_eq!(info.kind, FunctionKind::Scalar);
+    /// assert!(info.is_pure);
+    /// ```
+    pub fn functions(&self) -> Vec<FunctionInfo> {
+        unsafe {
+            let l = (T::api().symbol_table_get_function_list)(self.sym);
+            let kinds = (*l).get_kinds();
+            let arities = (*l).get_arities();
+            let pure = (*l).get_pure();
+            let out = (*l)
+                .get_names()
+                .iter()
+                .enumerate()
+                .map(|(i, n)| FunctionInfo {
+                    name: string_from_ptr!(*n),
+                    arity: if arities[i] < 0 {
+                        None
+                    } else {
+                        Some(arities[i] as usize)
+                    },
+                    kind: FunctionKind::from_i32(kinds[i]).unwrap_or_else(|| {
+                        panic!("Unknown FunctionKind enum variant: {}", kinds[i])
+                    }),
+                    is_pure: pure[i],
+                })
+                .collect();
+            function_list_free(l);
+            out
+        }
+    }
+
     pub fn symbol_exists(&self, name: &str) -> Result<bool, InvalidName> {
         let c_name = c_string(name)?;
-        let rv = unsafe { symbol_table_symbol_exists(self.sym, c_name.as_ptr()) };
+        let rv = unsafe { (T::api().symbol_table_symbol_exists)(self.sym, c_name.as_ptr()) };
         Ok(rv)
     }
 
     pub fn is_constant_node(&self, name: &str) -> Result<bool, InvalidName> {
         let c_name = c_string(name)?;
-        let rv = unsafe { symbol_table_is_constant_node(self.sym, c_name.as_ptr()) };
+        let rv = unsafe { (T::api().symbol_table_is_constant_node)(self.sym, c_name.as_ptr()) };
         Ok(rv)
     }
 
     pub fn is_constant_string(&self, name: &str) -> Result<bool, InvalidName> {
         let c_name = c_string(name)?;
-        let rv = unsafe { symbol_table_is_constant_string(self.sym, c_name.as_ptr()) };
+        let rv = unsafe { (T::api().symbol_table_is_constant_string)(self.sym, c_name.as_ptr()) };
         Ok(rv)
     }
 }
@@ -705,16 +1795,17 @@ impl SymbolTable {
 macro_rules! func_impl {
     ($name:ident, $n:expr, $sys_func:ident, $clone_func:ident, $free_closure:ident, $free_cpp_func:ident,
         $($x:ident: $ty:ty),*) => {
-        impl SymbolTable {
+        impl<T: Numeric> SymbolTable<T> {
             /// Add a function with
             #[doc = $n]
             /// scalar arguments. Returns `true` if the function was added / `false`
-            /// if the name was already present.
+            /// if the name was already present. The closure must be `Send`, since
+            /// the symbol table can be sent to other threads.
             pub fn $name<F>(&mut self, name: &str, func: F) -> Result<bool, InvalidName>
-                where F: Fn($($ty),*) -> c_double + Clone
+                where F: Fn($($ty),*) -> T + Clone + Send + 'static
             {
-                extern fn wrapper<F>(closure: *mut c_void, $($x: $ty),*) -> c_double
-                    where F: Fn($($ty),*) -> c_double {
+                extern fn wrapper<T: Numeric, F>(closure: *mut c_void, $($x: $ty),*) -> T
+                    where F: Fn($($ty),*) -> T {
                     unsafe {
                         let opt_closure: Option<Box<F>> = mem::transmute(closure);
                         opt_closure.map(|f| f($($x),*)).unwrap()
@@ -726,7 +1817,7 @@ macro_rules! func_impl {
 
                 let c_name = c_string(name)?;
                 let result = unsafe {
-                    $sys_func(self.sym, c_name.as_ptr(), wrapper::<F>, func_ptr)
+                    (T::api().$sys_func)(self.sym, c_name.as_ptr(), wrapper::<T, F>, func_ptr)
                 };
 
                 let is_new = self.validate_added(name, result.0, ())?.is_some();
@@ -735,18 +1826,18 @@ macro_rules! func_impl {
                         name: name.to_string(),
                         cpp_func: result.1,
                         rust_closure: func_ptr,
-                        clone_func: $clone_func::<F>,
-                        free_cpp_func: $free_cpp_func,
-                        free_closure_func: $free_closure::<F>,
+                        clone_func: $clone_func::<T, F>,
+                        free_cpp_func: T::api().$free_cpp_func,
+                        free_closure_func: $free_closure::<T, F>,
                     });
                 }
                 Ok(is_new)
             }
         }
 
-        fn $clone_func<F>(name: &str, closure_ptr: *mut c_void, new_symbols: &mut SymbolTable)
+        fn $clone_func<T: Numeric, F>(name: &str, closure_ptr: *mut c_void, new_symbols: &mut SymbolTable<T>)
         -> Result<bool, InvalidName>
-        where F: Fn($($ty),*) -> c_double + Clone
+        where F: Fn($($ty),*) -> T + Clone + Send + 'static
         {
             let mut opt_closure: Option<Box<F>> = unsafe { mem::transmute(closure_ptr) };
             let res = new_symbols.$name(name, *opt_closure.as_mut().unwrap().clone());
@@ -754,8 +1845,8 @@ macro_rules! func_impl {
             res
         }
 
-        fn $free_closure<F>(closure_ptr: *mut c_void)
-        where F: Fn($($ty),*) -> c_double + Clone
+        fn $free_closure<T: Numeric, F>(closure_ptr: *mut c_void)
+        where F: Fn($($ty),*) -> T + Clone + Send + 'static
         {
             let _: Option<Box<F>> = unsafe { mem::transmute(closure_ptr) };
         }
@@ -769,7 +1860,7 @@ func_impl!(
     clone_func1,
     free_func_closure1,
     symbol_table_free_func1,
-    a: c_double
+    a: T
 );
 func_impl!(
     add_func2,
@@ -778,8 +1869,8 @@ func_impl!(
     clone_func2,
     free_func_closure2,
     symbol_table_free_func2,
-    a: c_double,
-    b: c_double
+    a: T,
+    b: T
 );
 func_impl!(
     add_func3,
@@ -788,9 +1879,9 @@ func_impl!(
     clone_func3,
     free_func_closure3,
     symbol_table_free_func3,
-    a: c_double,
-    b: c_double,
-    c: c_double
+    a: T,
+    b: T,
+    c: T
 );
 func_impl!(
     add_func4,
@@ -799,10 +1890,10 @@ func_impl!(
     clone_func4,
     free_func_closure4,
     symbol_table_free_func4,
-    a: c_double,
-    b: c_double,
-    c: c_double,
-    d: c_double
+    a: T,
+    b: T,
+    c: T,
+    d: T
 );
 func_impl!(
     add_func5,
@@ -811,11 +1902,11 @@ func_impl!(
     clone_func5,
     free_func_closure5,
     symbol_table_free_func5,
-    a: c_double,
-    b: c_double,
-    c: c_double,
-    d: c_double,
-    e: c_double
+    a: T,
+    b: T,
+    c: T,
+    d: T,
+    e: T
 );
 func_impl!(
     add_func6,
@@ -824,12 +1915,12 @@ func_impl!(
     clone_func6,
     free_func_closure6,
     symbol_table_free_func6,
-    a: c_double,
-    b: c_double,
-    c: c_double,
-    d: c_double,
-    e: c_double,
-    f: c_double
+    a: T,
+    b: T,
+    c: T,
+    d: T,
+    e: T,
+    f: T
 );
 func_impl!(
     add_func7,
@@ -838,13 +1929,13 @@ func_impl!(
     clone_func7,
     free_func_closure7,
     symbol_table_free_func7,
-    a: c_double,
-    b: c_double,
-    c: c_double,
-    d: c_double,
-    e: c_double,
-    f: c_double,
-    g: c_double
+    a: T,
+    b: T,
+    c: T,
+    d: T,
+    e: T,
+    f: T,
+    g: T
 );
 func_impl!(
     add_func8,
@@ -853,14 +1944,14 @@ func_impl!(
     clone_func8,
     free_func_closure8,
     symbol_table_free_func8,
-    a: c_double,
-    b: c_double,
-    c: c_double,
-    d: c_double,
-    e: c_double,
-    f: c_double,
-    g: c_double,
-    h: c_double
+    a: T,
+    b: T,
+    c: T,
+    d: T,
+    e: T,
+    f: T,
+    g: T,
+    h: T
 );
 func_impl!(
     add_func9,
@@ -869,15 +1960,15 @@ func_impl!(
     clone_func9,
     free_func_closure9,
     symbol_table_free_func9,
-    a: c_double,
-    b: c_double,
-    c: c_double,
-    d: c_double,
-    e: c_double,
-    f: c_double,
-    g: c_double,
-    h: c_double,
-    i: c_double
+    a: T,
+    b: T,
+    c: T,
+    d: T,
+    e: T,
+    f: T,
+    g: T,
+    h: T,
+    i: T
 );
 func_impl!(
     add_func10,
@@ -886,25 +1977,39 @@ func_impl!(
     clone_func10,
     free_func_closure10,
     symbol_table_free_func10,
-    a: c_double,
-    b: c_double,
-    c: c_double,
-    d: c_double,
-    e: c_double,
-    f: c_double,
-    g: c_double,
-    h: c_double,
-    i: c_double,
-    j: c_double
+    a: T,
+    b: T,
+    c: T,
+    d: T,
+    e: T,
+    f: T,
+    g: T,
+    h: T,
+    i: T,
+    j: T
 );
 
-impl Default for exprtk::SymbolTable {
+impl<T: Numeric> Default for SymbolTable<T> {
     fn default() -> Self {
-        Self::new()
+        SymbolTable {
+            sym: unsafe { (T::api().symbol_table_new)() },
+            values: vec![],
+            strings: vec![],
+            vectors: vec![],
+            views: vec![],
+            const_strings: vec![],
+            const_vectors: vec![],
+            funcs: vec![],
+            polynomials: vec![],
+            #[cfg(any(feature = "rtl_vecops", feature = "all"))]
+            vecops: false,
+            #[cfg(any(feature = "rtl_io", feature = "all"))]
+            io: None,
+        }
     }
 }
 
-impl Drop for SymbolTable {
+impl<T: Numeric> Drop for SymbolTable<T> {
     fn drop(&mut self) {
         // strings have their owne destructor, but function pointers need to be freed
         for f in &self.funcs {
@@ -913,11 +2018,18 @@ impl Drop for SymbolTable {
                 (f.free_closure_func)(f.rust_closure);
             }
         }
-        unsafe { symbol_table_destroy(self.sym) };
+        for p in &self.polynomials {
+            unsafe { (T::api().symbol_table_free_polynomial)(p.cpp_func) };
+        }
+        #[cfg(any(feature = "rtl_io", feature = "all"))]
+        if let Some(io) = &self.io {
+            unsafe { (T::api().symbol_table_free_io_package)(io.cpp_package) };
+        }
+        unsafe { (T::api().symbol_table_destroy)(self.sym) };
     }
 }
 
-impl fmt::Debug for SymbolTable {
+impl<T: Numeric> fmt::Debug for SymbolTable<T> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let varnames = self.get_variable_names();
         write!(f,
@@ -938,21 +2050,20 @@ impl fmt::Debug for SymbolTable {
             ),
             format!("[{}]", self.get_stringvar_names()
                 .iter()
-                .map(|n| format!("\"{}\": \"{}\"", n,
-                    self.string(self.get_string_id(n).unwrap().unwrap()).get())
-                )
+                .map(|n| format!("\"{}\": \"{}\"", n, self.string_from_name(n).unwrap().get()))
                 .collect::<Vec<_>>()
                 .join(", ")
             ),
             format!("[{}]", self.get_vector_names()
                 .iter()
-                .map(|n| format!("\"{}\": {:?}", n, self.vector(self.get_vec_id(n).unwrap().unwrap())))
+                .map(|n| format!("\"{}\": {:?}", n, self.vector_from_name(n).unwrap()))
                 .collect::<Vec<_>>()
                 .join(", ")
             ),
             format!("[{}]", self.funcs
                 .iter()
                 .map(|f| f.name.to_string())
+                .chain(self.polynomials.iter().map(|p| p.name.to_string()))
                 .collect::<Vec<_>>()
                 .join(", ")
             ),
@@ -960,9 +2071,9 @@ impl fmt::Debug for SymbolTable {
     }
 }
 
-impl Clone for SymbolTable {
-    fn clone(&self) -> SymbolTable {
-        let mut s = Self::new();
+impl<T: Numeric> Clone for SymbolTable<T> {
+    fn clone(&self) -> SymbolTable<T> {
+        let mut s = Self::default();
         // vars
         for n in self.get_variable_names() {
             let v = self.value_from_name(&n).unwrap();
@@ -974,22 +2085,159 @@ impl Clone for SymbolTable {
         }
         // strings
         for n in self.get_stringvar_names() {
-            let v = self.string(self.get_string_id(&n).unwrap().unwrap()).get();
-            s.add_stringvar(&n, &v).unwrap();
+            let v = self.string_from_name(&n).unwrap().get();
+            if self.is_constant_string(&n).unwrap() {
+                s.add_string_constant(&n, v).unwrap();
+            } else {
+                s.add_stringvar(&n, v).unwrap();
+            }
         }
         // vectors
+        let mut views = vec![];
         for n in self.get_vector_names() {
-            let v = self.vector(self.get_vec_id(&n).unwrap().unwrap());
-            s.add_vector(&n, v).unwrap();
+            if let Some(id) = self.get_vector_view_id(&n).unwrap() {
+                views.push((id, n));
+            } else if self.is_constant_vector(&n).unwrap() {
+                s.add_vector_constant(&n, self.vector_from_name(&n).unwrap())
+                    .unwrap();
+            } else {
+                s.add_vector(&n, self.vector_from_name(&n).unwrap())
+                    .unwrap();
+            }
+        }
+        // vector views (keeping their IDs)
+        views.sort();
+        for (id, n) in views {
+            let view = self.vector_view(id);
+            let new_id = s.add_vector_view(&n, view.capacity()).unwrap().unwrap();
+            let new_view = s.vector_view_mut(new_id);
+            new_view.rebind(view.data.clone());
+            new_view.set_len(view.len());
         }
         // functions
         for f in &self.funcs {
             (f.clone_func)(&f.name, f.rust_closure, &mut s).unwrap();
         }
+        for p in &self.polynomials {
+            s.add_polynomial(&p.name, &p.coefficients).unwrap();
+        }
+        #[cfg(any(feature = "rtl_vecops", feature = "all"))]
+        if self.vecops {
+            s.add_vecops_package();
+        }
+        #[cfg(any(feature = "rtl_io", feature = "all"))]
+        if let Some(io) = &self.io {
+            s.add_io_sink(io.sink.clone());
+        }
         s
     }
 }
 
+/// Vector whose length and data can be changed without recompiling the expressions
+/// using it (wrapping `exprtk::vector_view`). The length can be reduced from the
+/// initial capacity, and the data can be replaced by another buffer (`rebind()`).
+pub struct VectorView<T: Numeric = c_double> {
+    view: *mut CVectorView,
+    data: Vec<T>,
+    len: usize,
+    capacity: usize,
+}
+
+impl<T: Numeric> VectorView<T> {
+    fn new(mut data: Vec<T>) -> VectorView<T> {
+        let len = data.len();
+        let view = unsafe { (T::api().vector_view_new)(data.as_mut_ptr(), len as size_t) };
+        VectorView {
+            view,
+            data,
+            len,
+            capacity: len,
+        }
+    }
+
+    /// Returns the current length of the vector
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Always returns `false`, since vector views cannot be empty
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        false
+    }
+
+    /// Returns the maximum length of the vector
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Changes the length of the vector, which must be larger than zero and not exceed
+    /// the capacity or the length of the buffer the vector is bound to. Returns `false`
+    /// if the length is invalid.
+    pub fn set_len(&mut self, len: usize) -> bool {
+        if len == 0 || len > self.capacity || len > self.data.len() {
+            return false;
+        }
+        let ok = unsafe { (T::api().vector_view_set_size)(self.view, len as size_t) };
+        if ok {
+            self.len = len;
+        }
+        ok
+    }
+
+    /// Binds the vector to a new buffer without copying and sets the length to
+    /// the length of the buffer. The previous buffer is returned.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if `data` is empty or longer than the capacity.
+    pub fn rebind(&mut self, mut data: Vec<T>) -> Vec<T> {
+        assert!(
+            !data.is_empty() && data.len() <= self.capacity,
+            "Invalid buffer length for vector view: {} (capacity: {})",
+            data.len(),
+            self.capacity
+        );
+        unsafe { (T::api().vector_view_rebase)(self.view, data.as_mut_ptr()) };
+        mem::swap(&mut self.data, &mut data);
+        let len = self.data.len();
+        // always succeeds with a valid length
+        self.set_len(len);
+        data
+    }
+
+    /// Returns the current elements of the vector
+    #[inline]
+    pub fn as_slice(&self) -> &[T] {
+        &self.data[..self.len]
+    }
+
+    /// Returns the current elements of the vector as mutable slice
+    #[inline]
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        &mut self.data[..self.len]
+    }
+}
+
+impl<T: Numeric> Drop for VectorView<T> {
+    fn drop(&mut self) {
+        unsafe { (T::api().vector_view_destroy)(self.view) };
+    }
+}
+
+impl<T: Numeric> fmt::Debug for VectorView<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "VectorView {{ {:?}, capacity: {} }}",
+            self.as_slice(),
+            self.capacity
+        )
+    }
+}
+
 /// Wraps a string value and allows modifying it.
 pub struct StringValue(*mut CppString);
 
@@ -1022,6 +2270,25 @@ impl StringValue {
     }
 }
 
+impl StringValue {
+    // Exchanges the contents without copying
+    pub(crate) fn swap(&mut self, other: &mut StringValue) {
+        unsafe { cpp_string_swap(self.0, other.0) };
+    }
+}
+
+impl Clone for StringValue {
+    fn clone(&self) -> StringValue {
+        StringValue::new(self.get())
+    }
+}
+
+impl PartialEq for StringValue {
+    fn eq(&self, other: &StringValue) -> bool {
+        self.get() == other.get()
+    }
+}
+
 impl Drop for StringValue {
     fn drop(&mut self) {
         unsafe { cpp_string_free(self.0) };
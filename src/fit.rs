@@ -0,0 +1,308 @@
+use super::*;
+
+/// Result of `fit_curve()`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FitResult<T: Real = c_double> {
+    /// Names and estimated values of the parameters, in the order in which the
+    /// parser reported them as unknown symbols (use `param()` for looking up a
+    /// parameter by name)
+    pub params: Vec<(String, T)>,
+    /// Values of the expression with the estimated parameters
+    pub fitted: Vec<T>,
+    /// Observed minus fitted values
+    pub residuals: Vec<T>,
+    /// Estimated covariance matrix of the parameters (in the order of `params`)
+    pub covariance: Vec<Vec<T>>,
+    pub iterations: usize,
+}
+
+impl<T: Real> FitResult<T> {
+    /// Returns the value of a parameter, or `None` if there is no parameter with
+    /// this name.
+    pub fn param(&self, name: &str) -> Option<T> {
+        self.params.iter().find(|p| p.0 == name).map(|p| p.1)
+    }
+
+    /// Returns the sum of squared residuals.
+    pub fn sum_of_squares(&self) -> T {
+        T::from_f64(self.residuals.iter().map(|r| r.to_f64().powi(2)).sum())
+    }
+}
+
+/// Estimates the parameters of a formula from observed data by nonlinear least
+/// squares, using the Levenberg–Marquardt algorithm.
+///
+/// The independent variables are given as `(name, values)` pairs, and are added to
+/// the supplied symbol table (which may contain constants or functions used in
+/// the formula). All other unknown variables of the formula are parameters.
+/// Their starting values are taken from `initial`, parameters not listed there
+/// start at `1`. The iteration stops if the relative change of the sum of
+/// squares or of the parameters falls below `opts.tol`. If the sum of squares
+/// cannot be decreased any more before, the fit only succeeds if the gradient
+/// vanishes (the cosine of the angle between the residuals and each column of the
+/// Jacobian matrix is below the square root of `opts.tol`).
+///
+/// An error is also returned if an input variable is already present in the
+/// symbol table, if `initial` contains a name that is not a parameter of the
+/// formula, or if the input columns and `observed` have different lengths.
+///
+/// # Example:
+/// ```
+/// use exprtk_rs::*;
+///
+/// let x = [0., 1., 2., 3., 4., 5.];
+/// let y: Vec<_> = x.iter().map(|x| 2. * (-0.5 * x).exp() + 0.1).collect();
+///
+/// let res = fit_curve(
+///     "a * exp(-b * x) + c",
+///     SymbolTable::new(),
+///     &[("x", &x)],
+///     &y,
+///     &[("b", 0.1)],
+///     SolverOptions::default(),
+/// )
+/// .unwrap();
+///
+/// assert!((res.param("a").unwrap() - 2.).abs() < 1e-6);
+/// assert!((res.param("b").unwrap() - 0.5).abs() < 1e-6);
+/// assert!((res.param("c").unwrap() - 0.1).abs() < 1e-6);
+/// ```
+pub fn fit_curve<T: Real>(
+    formula: &str,
+    mut symbols: SymbolTable<T>,
+    inputs: &[(&str, &[T])],
+    observed: &[T],
+    initial: &[(&str, T)],
+    opts: SolverOptions,
+) -> Result<FitResult<T>, FitError> {
+    let mut input_ids = Vec::with_capacity(inputs.len());
+    for &(name, values) in inputs {
+        if values.len() != observed.len() {
+            return Err(FitError::LengthMismatch {
+                input: name.to_string(),
+                len: values.len(),
+                observations: observed.len(),
+            });
+        }
+        let id = symbols
+            .add_variable(name, T::default())?
+            .ok_or_else(|| FitError::InputExists(name.to_string()))?;
+        input_ids.push(id);
+    }
+    let (mut expr, params) = Expression::parse_vars(formula, symbols)?;
+    let param_ids: Vec<_> = params.iter().map(|p| p.1).collect();
+    let mut p: Vec<_> = params
+        .iter()
+        .map(|(name, _)| {
+            initial
+                .iter()
+                .find(|i| i.0 == name)
+                .map(|i| i.1.to_f64())
+                .unwrap_or(1.)
+        })
+        .collect();
+    if let Some(&(name, _)) = initial
+        .iter()
+        .find(|&&(name, _)| !params.iter().any(|p| p.0 == name))
+    {
+        return Err(FitError::UnknownParameter(name.to_string()));
+    }
+    let (n, m) = (observed.len(), p.len());
+    if n <= m {
+        return Err(FitError::TooFewObservations {
+            observations: n,
+            parameters: m,
+        });
+    }
+
+    let y: Vec<_> = observed.iter().map(|v| v.to_f64()).collect();
+    let batch: Vec<_> = input_ids
+        .iter()
+        .zip(inputs)
+        .map(|(&id, &(_, values))| (id, values))
+        .collect();
+    let mut model = |p: &[f64]| -> Result<Vec<f64>, FitError> {
+        for (&id, &v) in param_ids.iter().zip(p) {
+            *expr.symbols_mut().value_mut(id) = T::from_f64(v);
+        }
+        let mut out = vec![T::default(); n];
+        expr.eval_batch(&batch, &mut out);
+        let out: Vec<_> = out.into_iter().map(|v| v.to_f64()).collect();
+        if out.iter().all(|v| v.is_finite()) {
+            Ok(out)
+        } else {
+            Err(FitError::NotFinite)
+        }
+    };
+    let ssq = |f: &[f64]| -> f64 { y.iter().zip(f).map(|(y, f)| (y - f).powi(2)).sum() };
+
+    // step size for the numerical derivatives (central differences)
+    let h_rel = epsilon::<T>().cbrt();
+    let mut lambda = 1e-3;
+    let mut f = model(&p)?;
+    let mut s = ssq(&f);
+    let mut iterations = 0;
+    let mut converged = false;
+    while iterations < opts.max_iter && !converged {
+        iterations += 1;
+        let jac = jacobian_of(&mut model, &p, h_rel)?;
+        // normal equations: (J^T J + lambda diag(J^T J)) delta = J^T r
+        let jtj = mat_t_mat(&jac, m);
+        let jtr: Vec<f64> = (0..m)
+            .map(|j| (0..n).map(|i| jac[i][j] * (y[i] - f[i])).sum())
+            .collect();
+        loop {
+            let mut a = jtj.clone();
+            for (j, row) in a.iter_mut().enumerate() {
+                row[j] += lambda * jtj[j][j].max(1e-12);
+            }
+            let delta = solve(a, jtr.clone()).ok_or(FitError::Singular)?;
+            let p_new: Vec<_> = p.iter().zip(&delta).map(|(p, d)| p + d).collect();
+            let f_new = model(&p_new)?;
+            let s_new = ssq(&f_new);
+            if s_new <= s {
+                let small_step = p
+                    .iter()
+                    .zip(&delta)
+                    .all(|(p, d)| d.abs() <= opts.tol * (p.abs() + opts.tol));
+                converged = s - s_new <= opts.tol * s_new || small_step;
+                p = p_new;
+                f = f_new;
+                s = s_new;
+                lambda = (lambda / 10.).max(1e-12);
+                break;
+            }
+            lambda *= 10.;
+            if lambda > 1e12 {
+                // No decrease possible any more, which is only accepted at a stationary
+                // point: the residuals must be (almost) orthogonal to the columns of J
+                let r_norm = s.sqrt();
+                let stationary = jtr
+                    .iter()
+                    .zip(&jtj)
+                    .enumerate()
+                    .all(|(j, (g, row))| g.abs() <= opts.tol.sqrt() * row[j].sqrt() * r_norm);
+                if !stationary {
+                    return Err(FitError::NotConverged { iterations });
+                }
+                converged = true;
+                break;
+            }
+        }
+    }
+    if !converged {
+        return Err(FitError::NotConverged { iterations });
+    }
+
+    // covariance: (J^T J)^-1 * s^2
+    let jac = jacobian_of(&mut model, &p, h_rel)?;
+    let cov = invert(mat_t_mat(&jac, m)).ok_or(FitError::Singular)?;
+    let s2 = s / (n - m) as f64;
+    Ok(FitResult {
+        params: params
+            .into_iter()
+            .zip(&p)
+            .map(|((name, _), &v)| (name, T::from_f64(v)))
+            .collect(),
+        residuals: y.iter().zip(&f).map(|(y, f)| T::from_f64(y - f)).collect(),
+        fitted: f.into_iter().map(T::from_f64).collect(),
+        covariance: cov
+            .into_iter()
+            .map(|row| row.into_iter().map(|c| T::from_f64(c * s2)).collect())
+            .collect(),
+        iterations,
+    })
+}
+
+// machine epsilon of the numeric type
+fn epsilon<T: Real>() -> f64 {
+    if T::from_f64(1. + f64::from(f32::EPSILON) / 2.).to_f64() == 1. {
+        f64::from(f32::EPSILON)
+    } else {
+        f64::EPSILON
+    }
+}
+
+// Jacobian of the model (observations x parameters)
+fn jacobian_of<F>(model: &mut F, p: &[f64], h_rel: f64) -> Result<Vec<Vec<f64>>, FitError>
+where
+    F: FnMut(&[f64]) -> Result<Vec<f64>, FitError>,
+{
+    let mut cols = Vec::with_capacity(p.len());
+    let mut p_h = p.to_vec();
+    for j in 0..p.len() {
+        let h = h_rel * p[j].abs().max(1.);
+        p_h[j] = p[j] + h;
+        let f_plus = model(&p_h)?;
+        p_h[j] = p[j] - h;
+        let f_minus = model(&p_h)?;
+        p_h[j] = p[j];
+        cols.push(
+            f_plus
+                .iter()
+                .zip(&f_minus)
+                .map(|(a, b)| (a - b) / (2. * h))
+                .collect::<Vec<_>>(),
+        );
+    }
+    let n = cols.first().map(|c| c.len()).unwrap_or(0);
+    Ok((0..n)
+        .map(|i| cols.iter().map(|c| c[i]).collect())
+        .collect())
+}
+
+// J^T J
+fn mat_t_mat(jac: &[Vec<f64>], m: usize) -> Vec<Vec<f64>> {
+    (0..m)
+        .map(|j| {
+            (0..m)
+                .map(|k| jac.iter().map(|row| row[j] * row[k]).sum())
+                .collect()
+        })
+        .collect()
+}
+
+// Solves a x = b using Gaussian elimination with partial pivoting,
+// returns None if the matrix is (numerically) singular
+fn solve(mut a: Vec<Vec<f64>>, mut b: Vec<f64>) -> Option<Vec<f64>> {
+    let m = b.len();
+    let scale = a.iter().flatten().fold(0f64, |s, v| s.max(v.abs()));
+    for col in 0..m {
+        let pivot = (col..m).max_by(|&i, &j| a[i][col].abs().total_cmp(&a[j][col].abs()))?;
+        if a[pivot][col].abs() <= 1e-12 * scale || !a[pivot][col].is_finite() {
+            return None;
+        }
+        a.swap(col, pivot);
+        b.swap(col, pivot);
+        for row in col + 1..m {
+            let factor = a[row][col] / a[col][col];
+            let (upper, lower) = a.split_at_mut(row);
+            for (v, p) in lower[0][col..].iter_mut().zip(&upper[col][col..]) {
+                *v -= factor * p;
+            }
+            b[row] -= factor * b[col];
+        }
+    }
+    let mut x = vec![0.; m];
+    for row in (0..m).rev() {
+        let sum: f64 = (row + 1..m).map(|k| a[row][k] * x[k]).sum();
+        x[row] = (b[row] - sum) / a[row][row];
+    }
+    Some(x)
+}
+
+// Inverts a matrix column by column
+fn invert(a: Vec<Vec<f64>>) -> Option<Vec<Vec<f64>>> {
+    let m = a.len();
+    let mut cols = Vec::with_capacity(m);
+    for j in 0..m {
+        let mut e = vec![0.; m];
+        e[j] = 1.;
+        cols.push(solve(a.clone(), e)?);
+    }
+    Some(
+        (0..m)
+            .map(|i| cols.iter().map(|c| c[i]).collect())
+            .collect(),
+    )
+}
@@ -0,0 +1,147 @@
+use std::ffi::{CStr, CString};
+use std::ops::Range;
+
+use enum_primitive::FromPrimitive;
+use exprtk_sys::*;
+
+use super::*;
+
+enum_from_primitive! {
+    /// Kind of a token returned by `tokenize()`.
+    #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+    pub enum TokenKind {
+        /// Invalid symbol, number or string (e.g. missing closing quote)
+        Error,
+        Number,
+        /// Names of variables and functions as well as keywords
+        Symbol,
+        String,
+        /// Arithmetic, comparison and other operators (including `?` and `:`)
+        Operator,
+        /// `:=`, `+=`, `-=`, `*=`, `/=` and `%=`
+        Assignment,
+        /// Round, square and curly brackets
+        Bracket,
+        /// `,` and `;`
+        Separator
+    }
+}
+
+/// Token of a formula as returned by `tokenize()`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Token {
+    pub kind: TokenKind,
+    /// Token value as seen by ExprTk. For strings, this is the content without
+    /// quotes and with escape sequences resolved.
+    pub value: String,
+    /// Byte range of the token in the formula (including the quotes of strings)
+    pub span: Range<usize>,
+}
+
+/// Splits a formula into tokens using the lexer of ExprTk (`exprtk::lexer::generator`),
+/// which is the first step of `Expression::new()`. Whitespace and comments (if the
+/// `comments` feature is enabled) are skipped. Like in the parser, operators separated
+/// by whitespace are joined (`: =` becomes `:=`, spanning both parts) and implicit
+/// multiplications are inserted (`2x`), the latter with an empty byte range in front
+/// of the following token. `true` and `false` are replaced by the numbers `1` and `0`,
+/// spanning the original word.
+///
+/// The lexer stops at the first invalid token, which is returned as the last token
+/// with kind `TokenKind::Error`. An error is only returned if the formula contains
+/// a null byte.
+///
+/// # Example:
+/// ```
+/// use exprtk_rs::*;
+///
+/// let tokens = tokenize("x := 'a' + 1.5").unwrap();
+/// let kinds: Vec<_> = tokens.iter().map(|t| t.kind).collect();
+/// assert_eq!(kinds, vec![
+///     TokenKind::Symbol, TokenKind::Assignment, TokenKind::String,
+///     TokenKind::Operator, TokenKind::Number,
+/// ]);
+/// assert_eq!(tokens[2].value, "a");
+/// assert_eq!(tokens[2].span, 5..8);
+/// ```
+pub fn tokenize(formula: &str) -> ParseResult<Vec<Token>> {
+    let c_formula = CString::new(formula).map_err(|_| InvalidName(formula.to_string()))?;
+    unsafe {
+        let l = lexer_tokenize(c_formula.as_ptr());
+        let kinds = (*l).get_kinds();
+        let starts = (*l).get_starts();
+        let ends = (*l).get_ends();
+        let out = (*l)
+            .get_values()
+            .iter()
+            .enumerate()
+            .map(|(i, v)| Token {
+                kind: TokenKind::from_i32(kinds[i])
+                    .unwrap_or_else(|| panic!("Unknown TokenKind enum variant: {}", kinds[i])),
+                value: string_from_ptr!(*v),
+                span: starts[i]..ends[i],
+            })
+            .collect();
+        token_list_free(l);
+        Ok(out)
+    }
+}
+
+/// Renames variables and functions in a formula. The closure is called with the name
+/// of every symbol token (see `tokenize()`) and returns the new name, or `None` if
+/// the symbol should be kept. Since only symbols are replaced, string literals,
+/// comments and whitespace are left untouched. Keywords (e.g. `var` or `for`) and
+/// built-in functions (e.g. `sin`) are symbols as well, but they are always kept and
+/// not passed to the closure (see `is_reserved_name()`).
+///
+/// An error is returned if the formula contains an invalid token (e.g. an unclosed
+/// string literal) or if a new name is not a valid identifier.
+///
+/// # Example:
+/// ```
+/// use std::collections::HashMap;
+/// use exprtk_rs::*;
+///
+/// let mut names = HashMap::new();
+/// names.insert("x".to_string(), "width".to_string());
+///
+/// let formula = "x * 2 + max(x, 1) + ('x' == s)";
+/// let renamed = rename_symbols(formula, |name| names.get(name).cloned()).unwrap();
+/// assert_eq!(renamed, "width * 2 + max(width, 1) + ('x' == s)");
+/// ```
+pub fn rename_symbols<F>(formula: &str, mut rename: F) -> ParseResult<String>
+where
+    F: FnMut(&str) -> Option<String>,
+{
+    let mut out = String::with_capacity(formula.len());
+    let mut pos = 0;
+    for token in tokenize(formula)? {
+        match token.kind {
+            TokenKind::Error => {
+                let msg = format!("Invalid token in formula: '{}'", token.value);
+                return Err(ParseError::simple_syntax(&token.value, &msg));
+            }
+            TokenKind::Symbol if !is_reserved_name(&token.value) => {
+                if let Some(new_name) = rename(&token.value) {
+                    if !is_symbol(&new_name)? {
+                        let msg = format!("Invalid symbol name: '{}'", new_name);
+                        return Err(ParseError::simple_syntax(&new_name, &msg));
+                    }
+                    out.push_str(&formula[pos..token.span.start]);
+                    out.push_str(&new_name);
+                    pos = token.span.end;
+                }
+            }
+            _ => {}
+        }
+    }
+    out.push_str(&formula[pos..]);
+    Ok(out)
+}
+
+// Returns true if the name consists of a single symbol token
+fn is_symbol(name: &str) -> ParseResult<bool> {
+    let tokens = tokenize(name)?;
+    Ok(tokens.len() == 1
+        && tokens[0].kind == TokenKind::Symbol
+        && tokens[0].span == (0..name.len()))
+}
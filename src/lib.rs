@@ -24,8 +24,17 @@
 //! For each data type (scalars, strings and vectors), access IDs start at zero
 //! and are incremented on addition of new variables of the given type.
 //!
-//! As there is no guarantee that `double` is always `f64`, the `c_double` type is used all
-//! over the library. Other precisions are currently not supported.
+//! `SymbolTable` and `Expression` are generic over the numeric type, which is any type
+//! implementing [Numeric](trait.Numeric.html) (`f32` or `f64`). The default is
+//! `c_double`, since there is no guarantee that the C++ `double` is always `f64`.
+//! A single precision symbol table is created with `SymbolTable::<f32>::default()`.
+//! ExprTk also supports `long double`, which is not available here, since Rust has
+//! no equivalent type.
+//!
+//! With the `complex` feature, expressions can be evaluated with complex numbers
+//! ([ComplexSymbolTable](type.ComplexSymbolTable.html) and
+//! [ComplexExpression](type.ComplexExpression.html)), using the `Complex64` type of the
+//! `num-complex` crate for variables, vectors and functions.
 //!
 //! ExprTk does not handle non-ASCII encodings, therefore variable names and formulae are
 //! checked for non-ASCII characters or null bytes and will fail with an error.
@@ -62,6 +71,8 @@
 //! The function `Expression::parse_vars` will return a `Vec` containing the newly added variable
 //! names and their variable IDs.
 //! This works only for regular variables, not for strings or vectors.
+//! `Expression::resolve_unknown` allows declaring unknown symbols as constants, strings or
+//! vectors as well.
 //!
 //! ```
 //! use exprtk_rs::*;
@@ -118,13 +129,51 @@
 //! let mut expr = Expression::new("add(x, 1)", symbol_table).unwrap();
 //! assert_eq!(expr.value(), 2.);
 //! ```
+//!
+//! # Thread safety
+//!
+//! `SymbolTable` and `Expression` are `Send`, but not `Sync`. They can be moved to
+//! another thread, but not shared between threads, since values can be modified
+//! through shared references (`value_cell()` and `vector_of_cells()`). For the same
+//! reason, closures added as functions are required to be `Send + 'static`.
+//! Evaluating an expression in parallel requires one copy per thread,
+//! which is managed by [ExpressionPool](struct.ExpressionPool.html).
+//!
+//! ```compile_fail,E0277
+//! use std::thread;
+//! use exprtk_rs::*;
+//!
+//! let symbol_table = SymbolTable::new();
+//! // a SymbolTable cannot be shared
+//! thread::scope(|s| {
+//!     s.spawn(|| symbol_table.value_cell(0).set(1.));
+//!     s.spawn(|| symbol_table.value_cell(0).set(2.));
+//! });
+//! ```
 
 #[macro_use]
 extern crate enum_primitive;
 
+pub use builtins::*;
+#[cfg(feature = "complex")]
+pub use complex::*;
+pub use context::*;
 pub use error::*;
 pub use exprtk::*;
+pub use exprtk_sys::{Numeric, Real};
+pub use fit::*;
+pub use lexer::*;
 pub use libc::c_double;
+pub use names::*;
+#[cfg(feature = "complex")]
+pub use num_complex::Complex64;
+#[cfg(any(feature = "rtl_io", feature = "all"))]
+pub use output::*;
+pub use pool::*;
+pub use provider::*;
+pub use quadrature::*;
+pub use solver::*;
+pub use table::*;
 
 macro_rules! string_from_ptr {
     ($s:expr) => {
@@ -132,8 +181,22 @@ macro_rules! string_from_ptr {
     };
 }
 
+mod builtins;
+#[cfg(feature = "complex")]
+mod complex;
+mod context;
 mod error;
 mod exprtk;
+mod fit;
+mod lexer;
+mod names;
+#[cfg(any(feature = "rtl_io", feature = "all"))]
+mod output;
+mod pool;
+mod provider;
+mod quadrature;
+mod solver;
+mod table;
 
 #[cfg(test)]
 mod tests;
@@ -0,0 +1,217 @@
+use std::collections::HashMap;
+
+use super::*;
+
+/// Bidirectional mapping of arbitrary names (e.g. column headers containing spaces,
+/// dots, non-ASCII characters or leading digits) to valid ExprTk identifiers.
+///
+/// Identifiers are derived from the names by replacing every character other than
+/// ASCII letters, digits and `_` with `_`. Names not starting with a letter are
+/// prefixed with `v_`, and names clashing with reserved words or built-in functions
+/// (see `is_reserved_name()`) are suffixed with `_`. If the identifier is already
+/// used for another name, or present in the symbol table the name is added to
+/// (e.g. `pi` added by `SymbolTable::add_constants()`), a number is appended (`_2`,
+/// `_3`, ...). Valid names are therefore kept as they are, unless the identifier
+/// was already given to an earlier name (e.g. `a_b` after `a b`). The same sequence
+/// of names always results in the same identifiers. Identifiers are compared
+/// ignoring case if the `caseinsensitivity` feature is enabled.
+///
+/// # Example:
+/// ```
+/// use exprtk_rs::*;
+///
+/// let mut mapper = NameMapper::new();
+/// let mut symbol_table = SymbolTable::new();
+/// mapper.add_variable(&mut symbol_table, "body mass (kg)", 70.).unwrap();
+/// mapper.add_variable(&mut symbol_table, "body.mass.kg", 80.).unwrap();
+/// mapper.add_variable(&mut symbol_table, "2nd", 2.).unwrap();
+///
+/// assert_eq!(mapper.identifier("body mass (kg)"), Some("body_mass__kg_"));
+/// assert_eq!(mapper.identifier("body.mass.kg"), Some("body_mass_kg"));
+/// assert_eq!(mapper.identifier("2nd"), Some("v_2nd"));
+///
+/// let mut expr = Expression::new("body_mass__kg_ * v_2nd", symbol_table).unwrap();
+/// assert_eq!(expr.value(), 140.);
+///
+/// let err = Expression::new("body_mass_kg + 1", SymbolTable::new()).unwrap_err();
+/// assert!(mapper.translate_error(err).message.contains("body.mass.kg"));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct NameMapper {
+    identifiers: HashMap<String, String>,
+    originals: HashMap<String, String>,
+}
+
+impl NameMapper {
+    pub fn new() -> NameMapper {
+        Self::default()
+    }
+
+    /// Returns the identifier for a name, creating a new one if the name was not
+    /// mapped before.
+    pub fn map(&mut self, name: &str) -> &str {
+        self.map_with(name, |_| false)
+    }
+
+    /// Returns the identifier for a name like `map()`, but new identifiers are
+    /// also chosen not to clash with symbols present in the symbol table.
+    pub fn map_in<T: Numeric>(&mut self, symbols: &SymbolTable<T>, name: &str) -> &str {
+        self.map_with(name, |ident| symbols.symbol_exists(ident) != Ok(false))
+    }
+
+    fn map_with<F: Fn(&str) -> bool>(&mut self, name: &str, is_taken: F) -> &str {
+        if !self.identifiers.contains_key(name) {
+            let ident = self.new_identifier(name, is_taken);
+            self.originals.insert(key(&ident), name.to_string());
+            self.identifiers.insert(name.to_string(), ident);
+        }
+        &self.identifiers[name]
+    }
+
+    /// Returns the identifier for a name, or `None` if it was not mapped.
+    pub fn identifier(&self, name: &str) -> Option<&str> {
+        self.identifiers.get(name).map(|s| s.as_str())
+    }
+
+    /// Returns the original name of an identifier, or `None` if the identifier
+    /// was not created by this mapper.
+    pub fn original(&self, identifier: &str) -> Option<&str> {
+        self.originals.get(&key(identifier)).map(|s| s.as_str())
+    }
+
+    /// Returns the number of mapped names.
+    pub fn len(&self) -> usize {
+        self.identifiers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.identifiers.is_empty()
+    }
+
+    /// Adds a variable with the identifier for the given name to the symbol table
+    /// (see `SymbolTable::add_variable()`).
+    pub fn add_variable<T: Numeric>(
+        &mut self,
+        symbols: &mut SymbolTable<T>,
+        name: &str,
+        value: T,
+    ) -> Result<Option<usize>, InvalidName> {
+        let ident = self.map_in(symbols, name);
+        symbols.add_variable(ident, value)
+    }
+
+    /// Adds a constant with the identifier for the given name to the symbol table
+    /// (see `SymbolTable::add_constant()`).
+    pub fn add_constant<T: Numeric>(
+        &mut self,
+        symbols: &mut SymbolTable<T>,
+        name: &str,
+        value: T,
+    ) -> Result<bool, InvalidName> {
+        let ident = self.map_in(symbols, name);
+        symbols.add_constant(ident, value)
+    }
+
+    /// Adds a string variable with the identifier for the given name to the symbol
+    /// table (see `SymbolTable::add_stringvar()`).
+    pub fn add_stringvar<T: Numeric>(
+        &mut self,
+        symbols: &mut SymbolTable<T>,
+        name: &str,
+        text: &str,
+    ) -> Result<Option<usize>, InvalidName> {
+        let ident = self.map_in(symbols, name);
+        symbols.add_stringvar(ident, text)
+    }
+
+    /// Adds a vector with the identifier for the given name to the symbol table
+    /// (see `SymbolTable::add_vector()`).
+    pub fn add_vector<T: Numeric>(
+        &mut self,
+        symbols: &mut SymbolTable<T>,
+        name: &str,
+        vec: &[T],
+    ) -> Result<Option<usize>, InvalidName> {
+        let ident = self.map_in(symbols, name);
+        symbols.add_vector(ident, vec)
+    }
+
+    /// Translates identifiers (e.g. returned by `SymbolTable::get_variable_names()`)
+    /// back to the original names. Identifiers not created by this mapper are
+    /// returned unchanged.
+    pub fn original_names<I, S>(&self, identifiers: I) -> Vec<String>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        identifiers
+            .into_iter()
+            .map(|i| {
+                let i = i.as_ref();
+                self.original(i).unwrap_or(i).to_string()
+            })
+            .collect()
+    }
+
+    /// Replaces all identifiers created by this mapper in a text (e.g. an error
+    /// message) with the original names.
+    pub fn translate_message(&self, text: &str) -> String {
+        let mut out = String::with_capacity(text.len());
+        let mut start = None;
+        for (i, c) in text.char_indices().chain(Some((text.len(), ' '))) {
+            let is_ident_char = c.is_ascii_alphanumeric() || c == '_';
+            match (start, is_ident_char) {
+                (None, true) => start = Some(i),
+                (Some(s), false) => {
+                    let word = &text[s..i];
+                    out.push_str(self.original(word).unwrap_or(word));
+                    start = None;
+                }
+                _ => {}
+            }
+            if !is_ident_char && i < text.len() {
+                out.push(c);
+            }
+        }
+        out
+    }
+
+    /// Translates the identifiers in a parse error back to the original names.
+    pub fn translate_error(&self, err: ParseError) -> ParseError {
+        ParseError {
+            token_value: self.translate_message(&err.token_value),
+            message: self.translate_message(&err.message),
+            line: self.translate_message(&err.line),
+            ..err
+        }
+    }
+
+    fn new_identifier<F: Fn(&str) -> bool>(&self, name: &str, is_taken: F) -> String {
+        let mut base: String = name
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect();
+        if !base.starts_with(|c: char| c.is_ascii_alphabetic()) {
+            base.insert_str(0, "v_");
+        }
+        if is_reserved_name(&base) {
+            base.push('_');
+        }
+        let mut ident = base.clone();
+        let mut i = 2;
+        while self.originals.contains_key(&key(&ident)) || is_taken(&ident) {
+            ident = format!("{}_{}", base, i);
+            i += 1;
+        }
+        ident
+    }
+}
+
+// key for looking up identifiers
+fn key(identifier: &str) -> String {
+    if cfg!(any(feature = "caseinsensitivity", feature = "all")) {
+        identifier.to_ascii_lowercase()
+    } else {
+        identifier.to_string()
+    }
+}
@@ -0,0 +1,87 @@
+use std::io::{self, Write};
+use std::slice;
+use std::sync::{Arc, Mutex};
+
+use libc::{c_char, c_void, size_t};
+
+/// In-memory sink for the output of `print()` and `println()` (see
+/// `SymbolTable::add_io_package()`). Clones share the same buffer, so the
+/// output can be read while a clone is owned by the symbol table.
+///
+/// # Example:
+/// ```
+/// use exprtk_rs::*;
+///
+/// let output = OutputBuffer::new();
+/// let mut symbol_table = SymbolTable::new();
+/// symbol_table.add_io_package(output.clone());
+///
+/// let mut expr = Expression::new("println('x = ', 1.5); 1", symbol_table).unwrap();
+/// assert_eq!(expr.value(), 1.);
+/// assert_eq!(output.take(), "x =    1.50000\n");
+/// assert!(output.is_empty());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct OutputBuffer(Arc<Mutex<Vec<u8>>>);
+
+impl OutputBuffer {
+    pub fn new() -> OutputBuffer {
+        Self::default()
+    }
+
+    /// Returns the output collected so far and clears the buffer. Invalid UTF-8
+    /// is replaced.
+    pub fn take(&self) -> String {
+        let bytes = std::mem::take(&mut *self.lock());
+        String::from_utf8_lossy(&bytes).into_owned()
+    }
+
+    /// Returns the output collected so far without clearing the buffer.
+    pub fn contents(&self) -> String {
+        String::from_utf8_lossy(&self.lock()).into_owned()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<u8>> {
+        self.0.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Write for OutputBuffer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.lock().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+pub(crate) type Sink = Arc<Mutex<dyn Write + Send>>;
+
+pub(crate) fn new_sink<W: Write + Send + 'static>(sink: W) -> Sink {
+    Arc::new(Mutex::new(sink))
+}
+
+// IO package of a symbol table. Its address is handed to the C++ functions,
+// the sink can be replaced at any time.
+pub(crate) struct IoPackage {
+    pub sink: Sink,
+    pub cpp_package: *mut c_void,
+}
+
+pub(crate) extern "C" fn write_output(io: *mut c_void, text: *const c_char, len: size_t) {
+    let (io, text) = unsafe {
+        (
+            &*(io as *const IoPackage),
+            slice::from_raw_parts(text as *const u8, len),
+        )
+    };
+    let mut sink = io.sink.lock().unwrap_or_else(|e| e.into_inner());
+    // errors cannot be reported back to ExprTk
+    let _ = sink.write_all(text);
+}
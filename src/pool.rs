@@ -0,0 +1,155 @@
+use std::fmt;
+use std::sync::Mutex;
+
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+use super::*;
+
+/// Pool of copies of a compiled `Expression` for evaluating it from multiple
+/// threads. Every thread checks out an expression with `with()`, which is returned
+/// to the pool afterwards. New copies are only created (by cloning the original
+/// expression) if all existing ones are in use, so there is at most one copy per
+/// concurrently running thread.
+///
+/// The idle copies are kept in a shared list, they are not bound to a thread: a
+/// thread may get a different copy on every call, and copies are only dropped
+/// together with the pool. Since copies are reused, changes to the symbol table of
+/// a checked out expression (e.g. values of variables assigned in a formula) persist
+/// and are seen by the next user of that copy. Variables should therefore be set
+/// before every evaluation.
+///
+/// With the `rayon` feature, `eval_batch()` and `eval_batch_strided()` split the rows
+/// into chunks, which are evaluated in parallel. Otherwise, they run on the current
+/// thread. In both cases, the results are written in the order of the input rows.
+///
+/// # Example:
+/// ```
+/// use std::sync::Arc;
+/// use std::thread;
+/// use exprtk_rs::*;
+///
+/// let mut symbol_table = SymbolTable::new();
+/// let x_id = symbol_table.add_variable("x", 0.).unwrap().unwrap();
+/// let expr = Expression::new("x^2", symbol_table).unwrap();
+/// let pool = Arc::new(ExpressionPool::new(expr));
+///
+/// let handles: Vec<_> = (0..4)
+///     .map(|i| {
+///         let pool = pool.clone();
+///         thread::spawn(move || {
+///             pool.with(|expr| {
+///                 expr.symbols().value_cell(x_id).set(i as f64);
+///                 expr.value()
+///             })
+///         })
+///     })
+///     .collect();
+///
+/// let results: Vec<_> = handles.into_iter().map(|h| h.join().unwrap()).collect();
+/// assert_eq!(results, vec![0., 1., 4., 9.]);
+/// ```
+pub struct ExpressionPool<T: Numeric = c_double> {
+    // Cloning recompiles the expression, so the template has its own lock and
+    // checking out / returning idle copies is not blocked meanwhile.
+    template: Mutex<Expression<T>>,
+    idle: Mutex<Vec<Expression<T>>>,
+}
+
+impl<T: Numeric> ExpressionPool<T> {
+    /// Creates a new pool from a compiled expression, which is used as
+    /// template for all copies.
+    pub fn new(expr: Expression<T>) -> ExpressionPool<T> {
+        ExpressionPool {
+            template: Mutex::new(expr),
+            idle: Mutex::new(vec![]),
+        }
+    }
+
+    /// Checks out an expression from the pool (cloning the template if none is
+    /// available), calls the closure with it and returns it to the pool.
+    pub fn with<F, R>(&self, func: F) -> R
+    where
+        F: FnOnce(&mut Expression<T>) -> R,
+    {
+        let idle = self.idle.lock().unwrap().pop();
+        let mut expr = match idle {
+            Some(e) => e,
+            None => self.template.lock().unwrap().clone(),
+        };
+        let res = func(&mut expr);
+        self.idle.lock().unwrap().push(expr);
+        res
+    }
+
+    /// Returns the number of expression copies created so far, which are
+    /// currently not in use.
+    pub fn idle_count(&self) -> usize {
+        self.idle.lock().unwrap().len()
+    }
+
+    /// Evaluates the expression for rows of input data like `Expression::eval_batch()`.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if a variable ID is invalid, or if an input slice
+    /// is shorter than `out`.
+    pub fn eval_batch(&self, inputs: &[(usize, &[T])], out: &mut [T]) {
+        let inputs: Vec<_> = inputs.iter().map(|&(id, data)| (id, data, 1)).collect();
+        self.eval_batch_strided(&inputs, out)
+    }
+
+    /// Evaluates the expression for rows of strided input data like
+    /// `Expression::eval_batch_strided()`.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if a variable ID is invalid, a stride is zero, or if an
+    /// input slice does not contain enough elements for all rows of `out`.
+    #[cfg(not(feature = "rayon"))]
+    pub fn eval_batch_strided(&self, inputs: &[(usize, &[T], usize)], out: &mut [T]) {
+        self.with(|expr| expr.eval_batch_strided(inputs, out))
+    }
+
+    /// Evaluates the expression for rows of strided input data like
+    /// `Expression::eval_batch_strided()`, in parallel.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if a variable ID is invalid, a stride is zero, or if an
+    /// input slice does not contain enough elements for all rows of `out`.
+    #[cfg(feature = "rayon")]
+    pub fn eval_batch_strided(&self, inputs: &[(usize, &[T], usize)], out: &mut [T]) {
+        // a few chunks per thread for balancing the load
+        let n_chunks = rayon::current_num_threads() * 4;
+        let chunk_size = out.len() / n_chunks + 1;
+        out.par_chunks_mut(chunk_size)
+            .enumerate()
+            .for_each(|(i, out_chunk)| {
+                let start = i * chunk_size;
+                let chunk_inputs: Vec<_> = inputs
+                    .iter()
+                    .map(|&(id, data, stride)| {
+                        // too short inputs are reported by Expression::eval_batch_strided
+                        let data = start
+                            .checked_mul(stride)
+                            .and_then(|i| data.get(i..))
+                            .unwrap_or(&[]);
+                        (id, data, stride)
+                    })
+                    .collect();
+                self.with(|expr| expr.eval_batch_strided(&chunk_inputs, out_chunk));
+            });
+    }
+}
+
+impl<T: Numeric> fmt::Debug for ExpressionPool<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "ExpressionPool {{ expression: {:?}, idle: {} }}",
+            *self.template.lock().unwrap(),
+            self.idle_count()
+        )
+    }
+}
@@ -0,0 +1,102 @@
+use std::collections::{BTreeMap, HashMap};
+use std::hash::BuildHasher;
+
+use super::*;
+
+/// Source of values for unknown symbols, which are looked up while compiling an
+/// expression with `Expression::parse_provided()`. Only the symbols actually used
+/// in a formula are requested and added to the symbol table.
+///
+/// `SymbolProvider` is implemented for maps (`HashMap` and `BTreeMap`, and references
+/// to them) with `String` keys and values convertible to a `Declaration`
+/// (`T`, `String`, `Vec<T>` or `Declaration` itself), and for closures
+/// of the type `FnMut(&str) -> Option<Declaration<T>>`.
+pub trait SymbolProvider<T: Numeric = c_double> {
+    /// Returns the declaration for the given symbol name, or `None` if the name
+    /// is unknown.
+    fn provide(&mut self, name: &str) -> Option<Declaration<T>>;
+}
+
+impl<T: Numeric, F> SymbolProvider<T> for F
+where
+    F: FnMut(&str) -> Option<Declaration<T>>,
+{
+    fn provide(&mut self, name: &str) -> Option<Declaration<T>> {
+        self(name)
+    }
+}
+
+impl<T: Numeric, V, S> SymbolProvider<T> for HashMap<String, V, S>
+where
+    V: Clone + Into<Declaration<T>>,
+    S: BuildHasher,
+{
+    fn provide(&mut self, name: &str) -> Option<Declaration<T>> {
+        self.get(name).cloned().map(Into::into)
+    }
+}
+
+impl<T: Numeric, V, S> SymbolProvider<T> for &HashMap<String, V, S>
+where
+    V: Clone + Into<Declaration<T>>,
+    S: BuildHasher,
+{
+    fn provide(&mut self, name: &str) -> Option<Declaration<T>> {
+        self.get(name).cloned().map(Into::into)
+    }
+}
+
+impl<T: Numeric, V> SymbolProvider<T> for BTreeMap<String, V>
+where
+    V: Clone + Into<Declaration<T>>,
+{
+    fn provide(&mut self, name: &str) -> Option<Declaration<T>> {
+        self.get(name).cloned().map(Into::into)
+    }
+}
+
+impl<T: Numeric, V> SymbolProvider<T> for &BTreeMap<String, V>
+where
+    V: Clone + Into<Declaration<T>>,
+{
+    fn provide(&mut self, name: &str) -> Option<Declaration<T>> {
+        self.get(name).cloned().map(Into::into)
+    }
+}
+
+impl From<c_double> for Declaration<c_double> {
+    fn from(value: c_double) -> Self {
+        Declaration::Variable(value)
+    }
+}
+
+impl From<f32> for Declaration<f32> {
+    fn from(value: f32) -> Self {
+        Declaration::Variable(value)
+    }
+}
+
+#[cfg(feature = "complex")]
+impl From<Complex64> for Declaration<Complex64> {
+    fn from(value: Complex64) -> Self {
+        Declaration::Variable(value)
+    }
+}
+
+impl<T: Numeric> From<String> for Declaration<T> {
+    fn from(value: String) -> Self {
+        Declaration::String(value)
+    }
+}
+
+impl<T: Numeric> From<&str> for Declaration<T> {
+    fn from(value: &str) -> Self {
+        Declaration::String(value.to_string())
+    }
+}
+
+impl<T: Numeric> From<Vec<T>> for Declaration<T> {
+    fn from(value: Vec<T>) -> Self {
+        Declaration::Vector(value)
+    }
+}
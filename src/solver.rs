@@ -0,0 +1,320 @@
+use super::*;
+
+/// Settings for the solvers (`Expression::solve_brent()`, `solve_newton()` and
+/// `minimize_golden()`).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SolverOptions {
+    /// Absolute tolerance of the solution
+    pub tol: f64,
+    /// Maximum number of iterations
+    pub max_iter: usize,
+}
+
+impl Default for SolverOptions {
+    fn default() -> Self {
+        SolverOptions {
+            tol: 1e-10,
+            max_iter: 100,
+        }
+    }
+}
+
+/// Solution found by a solver.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Solution<T: Real = c_double> {
+    pub x: T,
+    /// Value of the expression at `x` (the residual `f(x) - target` for roots)
+    pub fx: T,
+    pub iterations: usize,
+}
+
+type SolverResult<T> = Result<Solution<T>, SolverError<T>>;
+
+impl<T: Real> Expression<T> {
+    /// Finds `x` in the interval `[a, b]` such that the expression evaluates to
+    /// `target`, using Brent's method. The function values at `a` and `b` must
+    /// be on different sides of the target. The variable is restored to its
+    /// previous value afterwards.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the variable ID is invalid.
+    ///
+    /// # Example:
+    /// ```
+    /// use exprtk_rs::*;
+    ///
+    /// let mut symbol_table = SymbolTable::new();
+    /// let x_id = symbol_table.add_variable("x", 0.).unwrap().unwrap();
+    /// let mut expr = Expression::new("x^2", symbol_table).unwrap();
+    ///
+    /// let sol = expr.solve_brent(x_id, 2., 0., 2., SolverOptions::default()).unwrap();
+    /// assert!((sol.x - 2f64.sqrt()).abs() < 1e-10);
+    ///
+    /// let err = expr.solve_brent(x_id, -1., 0., 2., SolverOptions::default());
+    /// assert!(matches!(err, Err(SolverError::NoBracket { .. })));
+    /// ```
+    pub fn solve_brent(
+        &mut self,
+        var_id: usize,
+        target: T,
+        a: T,
+        b: T,
+        opts: SolverOptions,
+    ) -> SolverResult<T> {
+        let target = target.to_f64();
+        self.with_var(var_id, |f| {
+            brent(&mut |x| f(x) - target, a.to_f64(), b.to_f64(), opts)
+        })
+    }
+
+    /// Finds `x` such that the expression evaluates to `target` using Newton's
+    /// method, starting at `x0`. The derivative is calculated numerically
+    /// (see `derivative()`). The variable is restored to its previous value
+    /// afterwards.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the variable ID is invalid.
+    pub fn solve_newton(
+        &mut self,
+        var_id: usize,
+        target: T,
+        x0: T,
+        opts: SolverOptions,
+    ) -> SolverResult<T> {
+        let target = target.to_f64();
+        let prev = self.symbols().value(var_id);
+        let mut x = x0.to_f64();
+        let mut res = Err(SolverError::NotConverged {
+            x: x0,
+            fx: T::from_f64(f64::NAN),
+            iterations: opts.max_iter,
+        });
+        for iter in 1..=opts.max_iter {
+            let fx = self.eval_at(var_id, x) - target;
+            // step size relative to the magnitude of x
+            let h = 1e-4 * x.abs().max(1.);
+            let dfx = self
+                .derivative(var_id, T::from_f64(x), T::from_f64(h))
+                .to_f64();
+            if !fx.is_finite() || !dfx.is_finite() {
+                res = Err(SolverError::NotFinite { x: T::from_f64(x) });
+                break;
+            }
+            if fx == 0. {
+                res = Ok(solution(x, fx, iter));
+                break;
+            }
+            if dfx == 0. {
+                res = Err(SolverError::ZeroDerivative { x: T::from_f64(x) });
+                break;
+            }
+            let dx = fx / dfx;
+            x -= dx;
+            if dx.abs() <= opts.tol {
+                let fx = self.eval_at(var_id, x) - target;
+                res = Ok(solution(x, fx, iter));
+                break;
+            }
+            res = Err(SolverError::NotConverged {
+                x: T::from_f64(x),
+                fx: T::from_f64(fx),
+                iterations: iter,
+            });
+        }
+        *self.symbols_mut().value_mut(var_id) = prev;
+        res
+    }
+
+    /// Finds the minimum of the expression in the interval `[a, b]` using
+    /// golden-section search. If there are several local minima, any of them may
+    /// be returned. The variable is restored to its previous value afterwards.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the variable ID is invalid.
+    ///
+    /// # Example:
+    /// ```
+    /// use exprtk_rs::*;
+    ///
+    /// let mut symbol_table = SymbolTable::new();
+    /// let x_id = symbol_table.add_variable("x", 0.).unwrap().unwrap();
+    /// let mut expr = Expression::new("(x - 1)^2 + 3", symbol_table).unwrap();
+    ///
+    /// let sol = expr.minimize_golden(x_id, -5., 5., SolverOptions::default()).unwrap();
+    /// assert!((sol.x - 1.).abs() < 1e-6);
+    /// assert!((sol.fx - 3.).abs() < 1e-12);
+    /// ```
+    pub fn minimize_golden(
+        &mut self,
+        var_id: usize,
+        a: T,
+        b: T,
+        opts: SolverOptions,
+    ) -> SolverResult<T> {
+        self.with_var(var_id, |f| golden_section(f, a.to_f64(), b.to_f64(), opts))
+    }
+
+    fn eval_at(&mut self, var_id: usize, x: f64) -> f64 {
+        *self.symbols_mut().value_mut(var_id) = T::from_f64(x);
+        self.value().to_f64()
+    }
+
+    // Calls the closure with a function evaluating the expression at a value of
+    // the variable, which is restored afterwards
+    fn with_var<F>(&mut self, var_id: usize, func: F) -> SolverResult<T>
+    where
+        F: FnOnce(&mut dyn FnMut(f64) -> f64) -> SolverResult<T>,
+    {
+        let prev = self.symbols().value(var_id);
+        let res = func(&mut |x| self.eval_at(var_id, x));
+        *self.symbols_mut().value_mut(var_id) = prev;
+        res
+    }
+}
+
+fn solution<T: Real>(x: f64, fx: f64, iterations: usize) -> Solution<T> {
+    Solution {
+        x: T::from_f64(x),
+        fx: T::from_f64(fx),
+        iterations,
+    }
+}
+
+fn brent<T: Real>(
+    f: &mut dyn FnMut(f64) -> f64,
+    mut a: f64,
+    mut b: f64,
+    opts: SolverOptions,
+) -> SolverResult<T> {
+    let mut fa = f(a);
+    let mut fb = f(b);
+    if !fa.is_finite() {
+        return Err(SolverError::NotFinite { x: T::from_f64(a) });
+    }
+    if !fb.is_finite() {
+        return Err(SolverError::NotFinite { x: T::from_f64(b) });
+    }
+    if fa * fb > 0. {
+        return Err(SolverError::NoBracket {
+            a: T::from_f64(a),
+            b: T::from_f64(b),
+            fa: T::from_f64(fa),
+            fb: T::from_f64(fb),
+        });
+    }
+    // b is the current estimate, c the other end of the bracketing interval
+    // and a the previous estimate
+    let (mut c, mut fc) = (b, fb);
+    let (mut d, mut e) = (0., 0.);
+    for iter in 1..=opts.max_iter {
+        if (fb > 0.) == (fc > 0.) {
+            c = a;
+            fc = fa;
+            d = b - a;
+            e = d;
+        }
+        if fc.abs() < fb.abs() {
+            a = b;
+            b = c;
+            c = a;
+            fa = fb;
+            fb = fc;
+            fc = fa;
+        }
+        let tol1 = 2. * f64::EPSILON * b.abs() + 0.5 * opts.tol;
+        let xm = 0.5 * (c - b);
+        if xm.abs() <= tol1 || fb == 0. {
+            return Ok(solution(b, fb, iter));
+        }
+        if e.abs() >= tol1 && fa.abs() > fb.abs() {
+            // inverse quadratic interpolation or secant method
+            let s = fb / fa;
+            let (mut p, mut q);
+            if a == c {
+                p = 2. * xm * s;
+                q = 1. - s;
+            } else {
+                let r = fb / fc;
+                q = fa / fc;
+                p = s * (2. * xm * q * (q - r) - (b - a) * (r - 1.));
+                q = (q - 1.) * (r - 1.) * (s - 1.);
+            }
+            if p > 0. {
+                q = -q;
+            }
+            p = p.abs();
+            let min1 = 3. * xm * q - (tol1 * q).abs();
+            let min2 = (e * q).abs();
+            if 2. * p < min1.min(min2) {
+                e = d;
+                d = p / q;
+            } else {
+                d = xm;
+                e = d;
+            }
+        } else {
+            // bisection
+            d = xm;
+            e = d;
+        }
+        a = b;
+        fa = fb;
+        b += if d.abs() > tol1 { d } else { tol1.copysign(xm) };
+        fb = f(b);
+        if !fb.is_finite() {
+            return Err(SolverError::NotFinite { x: T::from_f64(b) });
+        }
+    }
+    Err(SolverError::NotConverged {
+        x: T::from_f64(b),
+        fx: T::from_f64(fb),
+        iterations: opts.max_iter,
+    })
+}
+
+fn golden_section<T: Real>(
+    f: &mut dyn FnMut(f64) -> f64,
+    mut a: f64,
+    mut b: f64,
+    opts: SolverOptions,
+) -> SolverResult<T> {
+    let inv_phi = (5f64.sqrt() - 1.) / 2.;
+    let mut c = b - inv_phi * (b - a);
+    let mut d = a + inv_phi * (b - a);
+    let mut fc = f(c);
+    let mut fd = f(d);
+    for iter in 1..=opts.max_iter {
+        if !fc.is_finite() {
+            return Err(SolverError::NotFinite { x: T::from_f64(c) });
+        }
+        if !fd.is_finite() {
+            return Err(SolverError::NotFinite { x: T::from_f64(d) });
+        }
+        if (b - a).abs() <= opts.tol {
+            let (x, fx) = if fc < fd { (c, fc) } else { (d, fd) };
+            return Ok(solution(x, fx, iter));
+        }
+        if fc < fd {
+            b = d;
+            d = c;
+            fd = fc;
+            c = b - inv_phi * (b - a);
+            fc = f(c);
+        } else {
+            a = c;
+            c = d;
+            fc = fd;
+            d = a + inv_phi * (b - a);
+            fd = f(d);
+        }
+    }
+    let (x, fx) = if fc < fd { (c, fc) } else { (d, fd) };
+    Err(SolverError::NotConverged {
+        x: T::from_f64(x),
+        fx: T::from_f64(fx),
+        iterations: opts.max_iter,
+    })
+}
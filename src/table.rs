@@ -0,0 +1,284 @@
+use super::*;
+
+/// Interpolation method of table functions (see `SymbolTable::add_table_func()`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Interpolation {
+    /// Value of the last point at or before `x` (piecewise constant)
+    Step,
+    /// Linear interpolation between neighbouring points
+    Linear,
+    /// Natural cubic spline (second derivative zero at both ends)
+    CubicSpline,
+}
+
+/// Behaviour of table functions outside of the range of the table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Extrapolation {
+    /// Value at the nearest end of the table
+    Clamp,
+    /// Linear continuation with the slope at the nearest end of the table
+    /// (zero for `Interpolation::Step`, which is therefore the same as `Clamp`)
+    Linear,
+    /// NaN outside of the table
+    NaN,
+}
+
+impl<T: Real> SymbolTable<T> {
+    /// Adds a function of one argument interpolating a table of `(xs, ys)` points.
+    /// The `xs` must be finite and strictly increasing, and there must be at least
+    /// two points (one for `Interpolation::Step`). Returns `true` if the function
+    /// was added / `false` if the name was already present (see `add_func1()`).
+    /// The table is copied, NaN arguments result in NaN.
+    ///
+    /// # Example:
+    /// ```
+    /// use exprtk_rs::*;
+    ///
+    /// let mut symbol_table = SymbolTable::new();
+    /// symbol_table
+    ///     .add_table_func(
+    ///         "viscosity",
+    ///         &[0., 20., 40.],
+    ///         &[1.79, 1.00, 0.65],
+    ///         Interpolation::Linear,
+    ///         Extrapolation::Clamp,
+    ///     )
+    ///     .unwrap();
+    ///
+    /// let mut expr = Expression::new("viscosity(10) + viscosity(100)", symbol_table).unwrap();
+    /// assert!((expr.value() - (1.395 + 0.65)).abs() < 1e-12);
+    ///
+    /// let mut s = SymbolTable::new();
+    /// let (lin, nan) = (Interpolation::Linear, Extrapolation::NaN);
+    /// let err = s.add_table_func("f", &[1., 0.], &[1., 2.], lin, nan);
+    /// assert_eq!(err, Err(TableError::NotIncreasing { index: 1 }));
+    /// ```
+    pub fn add_table_func(
+        &mut self,
+        name: &str,
+        xs: &[T],
+        ys: &[T],
+        interpolation: Interpolation,
+        extrapolation: Extrapolation,
+    ) -> Result<bool, TableError> {
+        let min_points = if interpolation == Interpolation::Step {
+            1
+        } else {
+            2
+        };
+        let xs = check_axis(xs, min_points)?;
+        let ys = check_values(ys, xs.len())?;
+        let table = Table1::new(xs, ys, interpolation, extrapolation);
+        Ok(self.add_func1(name, move |x: T| T::from_f64(table.eval(x.to_f64())))?)
+    }
+
+    /// Adds a function of two arguments interpolating bilinearly on a grid. `zs`
+    /// contains the values at the grid points in row-major order, i.e.
+    /// `zs[i * ys.len() + j]` is the value at `(xs[i], ys[j])`. Both axes must be
+    /// finite and strictly increasing with at least two points. Outside of the
+    /// grid, each coordinate is extrapolated separately. Returns `true` if the
+    /// function was added / `false` if the name was already present.
+    ///
+    /// Only two-dimensional tables are supported, tables with more dimensions
+    /// have to be implemented with `add_func3()` etc.
+    ///
+    /// # Example:
+    /// ```
+    /// use exprtk_rs::*;
+    ///
+    /// let mut symbol_table = SymbolTable::new();
+    /// symbol_table
+    ///     .add_table_func2(
+    ///         "f",
+    ///         &[0., 1.],
+    ///         &[0., 10.],
+    ///         &[0., 1., 2., 3.],
+    ///         Extrapolation::Linear,
+    ///     )
+    ///     .unwrap();
+    ///
+    /// let mut expr = Expression::new("f(0.5, 5) + f(2, 0)", symbol_table).unwrap();
+    /// assert_eq!(expr.value(), 1.5 + 4.);
+    /// ```
+    pub fn add_table_func2(
+        &mut self,
+        name: &str,
+        xs: &[T],
+        ys: &[T],
+        zs: &[T],
+        extrapolation: Extrapolation,
+    ) -> Result<bool, TableError> {
+        let xs = check_axis(xs, 2)?;
+        let ys = check_axis(ys, 2)?;
+        let zs = check_values(zs, xs.len() * ys.len())?;
+        let table = Table2 {
+            xs,
+            ys,
+            zs,
+            extrapolation,
+        };
+        Ok(self.add_func2(name, move |x: T, y: T| {
+            T::from_f64(table.eval(x.to_f64(), y.to_f64()))
+        })?)
+    }
+}
+
+fn check_axis<T: Real>(xs: &[T], min_points: usize) -> Result<Vec<f64>, TableError> {
+    if xs.len() < min_points {
+        return Err(TableError::TooFewPoints {
+            points: xs.len(),
+            required: min_points,
+        });
+    }
+    let xs = check_values(xs, xs.len())?;
+    match xs.windows(2).position(|w| w[1] <= w[0]) {
+        Some(i) => Err(TableError::NotIncreasing { index: i + 1 }),
+        None => Ok(xs),
+    }
+}
+
+fn check_values<T: Real>(values: &[T], expected: usize) -> Result<Vec<f64>, TableError> {
+    if values.len() != expected {
+        return Err(TableError::LengthMismatch {
+            expected,
+            found: values.len(),
+        });
+    }
+    let values: Vec<_> = values.iter().map(|v| v.to_f64()).collect();
+    match values.iter().position(|v| !v.is_finite()) {
+        Some(index) => Err(TableError::NotFinite { index }),
+        None => Ok(values),
+    }
+}
+
+// Index of the segment [xs[i], xs[i + 1]] containing x (or the nearest segment
+// if x is outside of the table)
+fn segment(xs: &[f64], x: f64) -> usize {
+    xs.partition_point(|&v| v <= x).clamp(1, xs.len() - 1) - 1
+}
+
+#[derive(Clone)]
+struct Table1 {
+    xs: Vec<f64>,
+    ys: Vec<f64>,
+    // second derivatives at the points (cubic spline only)
+    d2: Vec<f64>,
+    interpolation: Interpolation,
+    extrapolation: Extrapolation,
+}
+
+impl Table1 {
+    fn new(
+        xs: Vec<f64>,
+        ys: Vec<f64>,
+        interpolation: Interpolation,
+        extrapolation: Extrapolation,
+    ) -> Self {
+        let d2 = if interpolation == Interpolation::CubicSpline {
+            spline_second_derivatives(&xs, &ys)
+        } else {
+            vec![]
+        };
+        Table1 {
+            xs,
+            ys,
+            d2,
+            interpolation,
+            extrapolation,
+        }
+    }
+
+    fn eval(&self, x: f64) -> f64 {
+        let n = self.xs.len();
+        let (first, last) = (self.xs[0], self.xs[n - 1]);
+        if x.is_nan() {
+            return f64::NAN;
+        }
+        if x < first || x > last {
+            let end = if x < first { 0 } else { n - 1 };
+            return match self.extrapolation {
+                Extrapolation::Clamp => self.ys[end],
+                Extrapolation::Linear => self.ys[end] + self.slope(end) * (x - self.xs[end]),
+                Extrapolation::NaN => f64::NAN,
+            };
+        }
+        if self.interpolation == Interpolation::Step {
+            return self.ys[self.xs.partition_point(|&v| v <= x) - 1];
+        }
+        let i = segment(&self.xs, x);
+        let h = self.xs[i + 1] - self.xs[i];
+        let t = (x - self.xs[i]) / h;
+        let linear = self.ys[i] + t * (self.ys[i + 1] - self.ys[i]);
+        if self.interpolation == Interpolation::Linear {
+            return linear;
+        }
+        linear - h * h / 6. * t * (1. - t) * ((2. - t) * self.d2[i] + (1. + t) * self.d2[i + 1])
+    }
+
+    // slope at the first or last point
+    fn slope(&self, end: usize) -> f64 {
+        if self.interpolation == Interpolation::Step {
+            return 0.;
+        }
+        let i = end.min(self.xs.len() - 2);
+        let h = self.xs[i + 1] - self.xs[i];
+        let secant = (self.ys[i + 1] - self.ys[i]) / h;
+        match (self.interpolation, end) {
+            (Interpolation::CubicSpline, 0) => secant - h / 6. * (2. * self.d2[0] + self.d2[1]),
+            (Interpolation::CubicSpline, _) => secant + h / 6. * (self.d2[i] + 2. * self.d2[i + 1]),
+            _ => secant,
+        }
+    }
+}
+
+// Solves the tridiagonal system for the second derivatives of a natural cubic
+// spline (Thomas algorithm)
+fn spline_second_derivatives(xs: &[f64], ys: &[f64]) -> Vec<f64> {
+    let n = xs.len();
+    let mut d2 = vec![0.; n];
+    let mut c = vec![0.; n];
+    for i in 1..n - 1 {
+        let (h0, h1) = (xs[i] - xs[i - 1], xs[i + 1] - xs[i]);
+        let rhs = 6. * ((ys[i + 1] - ys[i]) / h1 - (ys[i] - ys[i - 1]) / h0);
+        let diag = 2. * (h0 + h1) - h0 * c[i - 1];
+        c[i] = h1 / diag;
+        d2[i] = (rhs - h0 * d2[i - 1]) / diag;
+    }
+    for i in (1..n - 1).rev() {
+        d2[i] -= c[i] * d2[i + 1];
+    }
+    d2
+}
+
+#[derive(Clone)]
+struct Table2 {
+    xs: Vec<f64>,
+    ys: Vec<f64>,
+    zs: Vec<f64>,
+    extrapolation: Extrapolation,
+}
+
+impl Table2 {
+    fn eval(&self, x: f64, y: f64) -> f64 {
+        let (nx, ny) = (self.xs.len(), self.ys.len());
+        let outside = |v: f64, axis: &[f64]| v < axis[0] || v > axis[axis.len() - 1];
+        if x.is_nan() || y.is_nan() {
+            return f64::NAN;
+        }
+        let (x, y) = match self.extrapolation {
+            Extrapolation::Clamp => (
+                x.clamp(self.xs[0], self.xs[nx - 1]),
+                y.clamp(self.ys[0], self.ys[ny - 1]),
+            ),
+            Extrapolation::NaN if outside(x, &self.xs) || outside(y, &self.ys) => return f64::NAN,
+            _ => (x, y),
+        };
+        let (i, j) = (segment(&self.xs, x), segment(&self.ys, y));
+        let tx = (x - self.xs[i]) / (self.xs[i + 1] - self.xs[i]);
+        let ty = (y - self.ys[j]) / (self.ys[j + 1] - self.ys[j]);
+        let z = |i: usize, j: usize| self.zs[i * ny + j];
+        let z0 = z(i, j) + ty * (z(i, j + 1) - z(i, j));
+        let z1 = z(i + 1, j) + ty * (z(i + 1, j + 1) - z(i + 1, j));
+        z0 + tx * (z1 - z0)
+    }
+}
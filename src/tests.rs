@@ -111,6 +111,84 @@ fn test_resolver() {
     assert_relative_eq!(expr.value(), 12.);
 }
 
+#[test]
+fn test_resolver_unregistered() {
+    let res = Expression::handle_unknown("a + b", SymbolTable::new(), |_, _| Ok(()));
+    assert!(res.unwrap_err().message.contains("'a' was not registered"));
+}
+
+#[test]
+fn test_declaration_resolver() {
+    let mut s = SymbolTable::new();
+    s.add_variable("a", 1.).unwrap().unwrap();
+    let (mut expr, resolved) =
+        Expression::resolve_unknown("a + b + c + s[] + v[]", s, |name| match name {
+            "b" => Declaration::Variable(1.),
+            "c" => Declaration::Constant(1.),
+            "s" => Declaration::String("string".to_string()),
+            "v" => Declaration::Vector(vec![1., 2., 3.]),
+            _ => Declaration::Reject("unexpected".to_string()),
+        })
+        .unwrap();
+    assert_relative_eq!(expr.value(), 12.);
+    assert!(expr.symbols().is_constant_node("c").unwrap());
+    assert_eq!(
+        resolved,
+        vec![
+            ("b".to_string(), SymbolType::Variable, 1),
+            ("s".to_string(), SymbolType::String, 0),
+            ("v".to_string(), SymbolType::Vector, 0),
+        ]
+    );
+    assert_eq!(expr.symbols().get_var_id("b").unwrap(), Some(1));
+
+    let res = Expression::resolve_unknown("a + b", SymbolTable::new(), |name| match name {
+        "a" => Declaration::Variable(1.),
+        _ => Declaration::Reject("unexpected".to_string()),
+    });
+    assert!(res.unwrap_err().message.contains("unexpected"));
+}
+
+#[test]
+fn test_provider() {
+    use std::collections::{BTreeMap, HashMap};
+
+    let mut values = HashMap::new();
+    values.insert("a".to_string(), 1.);
+    values.insert("b".to_string(), 2.);
+    values.insert("c".to_string(), 3.);
+    let (mut expr, vars) =
+        Expression::parse_provided("c + a", SymbolTable::new(), &values).unwrap();
+    assert_eq!(
+        vars,
+        vec![
+            ("c".to_string(), SymbolType::Variable, 0),
+            ("a".to_string(), SymbolType::Variable, 1)
+        ]
+    );
+    assert_relative_eq!(expr.value(), 4.);
+    assert_eq!(expr.symbols().get_variable_names(), vec!["a", "c"]);
+
+    let mut strings = BTreeMap::new();
+    strings.insert("s".to_string(), "string".to_string());
+    let (mut expr, vars) = Expression::parse_provided("s[]", SymbolTable::new(), strings).unwrap();
+    assert_eq!(vars, vec![("s".to_string(), SymbolType::String, 0)]);
+    assert_relative_eq!(expr.value(), 6.);
+
+    let provider = |name: &str| match name {
+        "v" => Some(Declaration::Vector(vec![1., 2.])),
+        "k" => Some(Declaration::Constant(1.)),
+        _ => None,
+    };
+    let (mut expr, vars) =
+        Expression::parse_provided("v[1] + k", SymbolTable::new(), provider).unwrap();
+    assert_eq!(vars, vec![("v".to_string(), SymbolType::Vector, 0)]);
+    assert_relative_eq!(expr.value(), 3.);
+
+    let res = Expression::parse_provided("a + d", SymbolTable::new(), &values);
+    assert!(res.unwrap_err().message.contains("Undefined symbol: 'd'"));
+}
+
 #[test]
 fn test_auto_resolver() {
     let (mut expr, vars) = Expression::parse_vars("a + b", SymbolTable::new()).unwrap();
@@ -120,6 +198,87 @@ fn test_auto_resolver() {
     assert_relative_eq!(expr.value(), 1.);
 }
 
+#[test]
+fn test_assignments() {
+    let mut s = SymbolTable::new();
+    let x_id = s.add_variable("x", 1.).unwrap().unwrap();
+    let y_id = s.add_variable("y", 0.).unwrap().unwrap();
+    s.add_variable("z", 0.).unwrap().unwrap();
+    s.add_stringvar("s", "").unwrap().unwrap();
+    let v_id = s.add_vector("v", &[0., 0.]).unwrap().unwrap();
+    let mut expr = Expression::new("y := x * 2; z += 1; s := 'a'; v[1] := x; x", s).unwrap();
+    assert_eq!(expr.assigned_variables(), vec!["y", "z"]);
+    assert_eq!(expr.assigned_strings(), vec!["s"]);
+    assert_eq!(expr.assigned_vectors(), vec!["v"]);
+    // sorted by name
+    assert_eq!(
+        expr.assigned_symbols()[1],
+        ("v".to_string(), SymbolType::VectorElement)
+    );
+    assert_relative_eq!(expr.value(), 1.);
+    assert_relative_eq!(expr.symbols().value(y_id), 2.);
+    assert_relative_eq!(expr.symbols().vector(v_id)[1], 1.);
+
+    let expr = Expression::new("x + y", expr.symbols().clone()).unwrap();
+    assert!(expr.assigned_symbols().is_empty());
+    assert_eq!(expr.symbols().value(x_id), 1.);
+}
+
+#[test]
+fn test_recompile() {
+    let mut s = SymbolTable::new();
+    let a_id = s.add_variable("a", 1.).unwrap().unwrap();
+    let v_id = s.add_vector("v", &[1., 2.]).unwrap().unwrap();
+    let mut expr = Expression::new("a + 1", s).unwrap();
+    assert_relative_eq!(expr.value(), 2.);
+    expr.recompile("var b := a * 2; v[1] + b").unwrap();
+    assert_relative_eq!(expr.value(), 4.);
+    assert_eq!(format!("{:?}", expr.clone()), format!("{:?}", expr));
+
+    let e = expr.recompile("a + c").unwrap_err();
+    assert_eq!(e.token_value, "c".to_string());
+    *expr.symbols_mut().value_mut(a_id) = 2.;
+    assert_relative_eq!(expr.value(), 6.);
+
+    let s = expr.into_symbols();
+    assert_relative_eq!(s.value(a_id), 2.);
+    assert_eq!(s.vector(v_id), &[1., 2.]);
+    let mut expr = Expression::new("a + v[0]", s).unwrap();
+    assert_relative_eq!(expr.value(), 3.);
+}
+
+#[test]
+fn test_introspection() {
+    let mut s = SymbolTable::new();
+    s.add_variable("x", 1.).unwrap().unwrap();
+    s.add_variable("y", 1.).unwrap().unwrap();
+    s.add_constant("c", 2.).unwrap();
+
+    let e = Expression::new("c * 2 + 1", s.clone()).unwrap();
+    assert_eq!(e.formula(), "c * 2 + 1");
+    assert!(e.is_constant());
+    assert!(!e.is_variable());
+
+    let e = Expression::new("x", s.clone()).unwrap();
+    assert!(e.is_variable());
+    assert!(!e.is_constant());
+
+    let e = Expression::new("-x", s.clone()).unwrap();
+    assert!(e.is_unary());
+
+    let e = Expression::new("x + y", s.clone()).unwrap();
+    assert!(e.is_binary());
+    assert!(!e.is_function());
+
+    let mut s2 = s.clone();
+    s2.add_func2("f", |a, b| a + b).unwrap();
+    let e = Expression::new("f(x, y)", s2).unwrap();
+    assert!(e.is_function());
+
+    let e = Expression::new("null", s).unwrap();
+    assert!(e.is_null());
+}
+
 #[test]
 fn test_names() {
     let mut s = SymbolTable::new();
@@ -198,3 +357,901 @@ fn test_send() {
         assert_relative_eq!(e.value(), 6.);
     });
 }
+
+#[test]
+fn test_f32() {
+    let mut s = SymbolTable::<f32>::default();
+    let x_id = s.add_variable("x", 1.5f32).unwrap().unwrap();
+    s.add_vector("v", &[1f32, 2.]).unwrap().unwrap();
+    s.add_func2("add", |a: f32, b: f32| a + b).unwrap();
+    let mut expr = Expression::new("add(x, v[1])", s).unwrap();
+    assert_relative_eq!(expr.value(), 3.5f32);
+    expr.symbols().value_cell(x_id).set(0.25);
+    assert_relative_eq!(expr.value(), 2.25f32);
+    assert_relative_eq!(expr.clone().value(), 2.25f32);
+
+    let (mut expr, vars) = Expression::parse_vars("a / 4", SymbolTable::<f32>::default()).unwrap();
+    assert_eq!(vars, vec![("a".to_string(), 0)]);
+    expr.symbols().value_cell(0).set(1.);
+    assert_eq!(expr.value(), 0.25f32);
+}
+
+#[cfg(feature = "complex")]
+#[test]
+fn test_complex() {
+    let mut s = ComplexSymbolTable::default();
+    assert!(s.add_imaginary_unit());
+    let z_id = s
+        .add_variable("z", Complex64::new(1., 1.))
+        .unwrap()
+        .unwrap();
+    s.add_vector("v", &[Complex64::new(0., 2.), Complex64::new(3., 0.)])
+        .unwrap()
+        .unwrap();
+    s.add_func1("conj", |z: Complex64| Complex64::new(z.re, -z.im))
+        .unwrap();
+    let mut expr = ComplexExpression::new("conj(z) * v[0] + 2 * i", s).unwrap();
+    assert_eq!(expr.value(), Complex64::new(2., 4.));
+    expr.symbols().value_cell(z_id).set(Complex64::new(0., -1.));
+    assert_eq!(expr.value(), Complex64::new(-2., 2.));
+    assert_eq!(expr.clone().value(), Complex64::new(-2., 2.));
+
+    let mut expr = ComplexExpression::new("abs(3 + 4 * i)", expr.into_symbols()).unwrap();
+    assert_eq!(expr.value(), Complex64::new(5., 0.));
+}
+
+#[test]
+fn test_eval_batch() {
+    let mut s = SymbolTable::new();
+    let x_id = s.add_variable("x", 0.).unwrap().unwrap();
+    let y_id = s.add_variable("y", 0.).unwrap().unwrap();
+    s.add_variable("c", 10.).unwrap().unwrap();
+    let mut expr = Expression::new("x - y + c", s).unwrap();
+
+    let mut out = [0.; 3];
+    expr.eval_batch(
+        &[(x_id, &[1., 2., 3., 4.]), (y_id, &[1., 1., 1.])],
+        &mut out,
+    );
+    assert_eq!(out, [10., 11., 12.]);
+    assert_eq!(expr.symbols().value(x_id), 3.);
+
+    // row-major table with two columns
+    let table = [1., 5., 2., 6., 3., 7.];
+    expr.eval_batch_strided(&[(x_id, &table[1..], 2), (y_id, &table, 2)], &mut out);
+    assert_eq!(out, [14., 14., 14.]);
+
+    let mut empty: [f64; 0] = [];
+    expr.eval_batch(&[(x_id, &[])], &mut empty);
+}
+
+#[test]
+#[should_panic(expected = "too short")]
+fn test_eval_batch_short() {
+    let mut s = SymbolTable::new();
+    let x_id = s.add_variable("x", 0.).unwrap().unwrap();
+    let mut expr = Expression::new("x", s).unwrap();
+    let mut out = [0.; 3];
+    expr.eval_batch_strided(&[(x_id, &[1., 2., 3., 4.], 2)], &mut out);
+}
+
+#[test]
+#[should_panic(expected = "too short")]
+fn test_eval_batch_stride_overflow() {
+    let mut s = SymbolTable::new();
+    let x_id = s.add_variable("x", 0.).unwrap().unwrap();
+    let mut expr = Expression::new("x", s).unwrap();
+    let mut out = [0.; 3];
+    expr.eval_batch_strided(&[(x_id, &[1., 2., 3., 4.], usize::MAX / 2)], &mut out);
+}
+
+#[test]
+fn test_pool() {
+    use std::sync::Arc;
+    use std::thread;
+
+    let mut s = SymbolTable::new();
+    let x_id = s.add_variable("x", 0.).unwrap().unwrap();
+    let y_id = s.add_variable("y", 0.).unwrap().unwrap();
+    let expr = Expression::new("x * 2 + y", s).unwrap();
+    let pool = Arc::new(ExpressionPool::new(expr));
+
+    let x: Vec<_> = (0..1000).map(|i| i as f64).collect();
+    // every second value is used
+    let y: Vec<_> = (0..2000).map(|i| -(i as f64)).collect();
+    let mut out = vec![0.; 1000];
+    pool.eval_batch_strided(&[(x_id, &x, 1), (y_id, &y, 2)], &mut out);
+    assert!(out.iter().all(|&v| v == 0.));
+    assert!(pool.idle_count() >= 1);
+
+    let handles: Vec<_> = (0..4)
+        .map(|i| {
+            let pool = pool.clone();
+            thread::spawn(move || {
+                pool.with(|e| {
+                    e.symbols().value_cell(x_id).set(i as f64);
+                    e.symbols().value_cell(y_id).set(1.);
+                    e.value()
+                })
+            })
+        })
+        .collect();
+    let res: Vec<_> = handles.into_iter().map(|h| h.join().unwrap()).collect();
+    assert_eq!(res, vec![1., 3., 5., 7.]);
+}
+
+// Compile-time checks of the thread safety guarantees, the runtime behaviour is
+// tested by test_threads_pool and test_threads_moved
+#[test]
+fn test_send_sync() {
+    fn assert_send<T: Send>() {}
+    fn assert_sync<T: Sync>() {}
+
+    assert_send::<SymbolTable>();
+    assert_send::<SymbolTable<f32>>();
+    assert_send::<Expression>();
+    assert_send::<StringValue>();
+    assert_sync::<StringValue>();
+    assert_send::<ExpressionPool>();
+    assert_sync::<ExpressionPool>();
+
+    // Using the ambiguity of method resolution to detect Sync implementations:
+    // if the type implemented Sync, `some_item` would be ambiguous.
+    trait AmbiguousIfSync<A> {
+        fn some_item() {}
+    }
+    impl<T: ?Sized> AmbiguousIfSync<()> for T {}
+    struct Invalid;
+    impl<T: ?Sized + Sync> AmbiguousIfSync<Invalid> for T {}
+    <SymbolTable as AmbiguousIfSync<_>>::some_item();
+    <Expression as AmbiguousIfSync<_>>::some_item();
+}
+
+#[test]
+fn test_threads_pool() {
+    use std::sync::Arc;
+    use std::thread;
+
+    let mut s = SymbolTable::new();
+    let x_id = s.add_variable("x", 0.).unwrap().unwrap();
+    s.add_func1("twice", |x| 2. * x).unwrap();
+    // assignments modify the symbol table of the checked out copy
+    let expr = Expression::new("var y := twice(x); y + 1", s).unwrap();
+    let pool = Arc::new(ExpressionPool::new(expr));
+
+    let handles: Vec<_> = (0..8)
+        .map(|t| {
+            let pool = pool.clone();
+            thread::spawn(move || {
+                (0..1000)
+                    .map(|i| {
+                        let x = (t * 1000 + i) as f64;
+                        let res = pool.with(|e| {
+                            e.symbols().value_cell(x_id).set(x);
+                            e.value()
+                        });
+                        (res == 2. * x + 1.) as usize
+                    })
+                    .sum::<usize>()
+            })
+        })
+        .collect();
+    let correct: usize = handles.into_iter().map(|h| h.join().unwrap()).sum();
+    assert_eq!(correct, 8000);
+    assert!(pool.idle_count() >= 1 && pool.idle_count() <= 8);
+}
+
+#[test]
+fn test_threads_moved() {
+    use std::thread;
+
+    let mut s = SymbolTable::new();
+    let x_id = s.add_variable("x", 0.).unwrap().unwrap();
+    let s_id = s.add_stringvar("s", "").unwrap().unwrap();
+    let offset = 10.;
+    s.add_func1("shift", move |x| x + offset).unwrap();
+    let expr = Expression::new("s := 'abc'; x := shift(x) + s[]", s).unwrap();
+
+    let handles: Vec<_> = (0..4)
+        .map(|t| {
+            let mut e = expr.clone();
+            thread::spawn(move || {
+                *e.symbols_mut().value_mut(x_id) = t as f64;
+                for _ in 0..100 {
+                    e.value();
+                }
+                e
+            })
+        })
+        .collect();
+    for (t, h) in handles.into_iter().enumerate() {
+        // expressions moved back from other threads are still usable
+        let mut e = h.join().unwrap();
+        assert_eq!(e.symbols().value(x_id), t as f64 + 100. * 13.);
+        assert_eq!(e.symbols().string(s_id).get(), "abc");
+        assert_eq!(e.value(), t as f64 + 101. * 13.);
+    }
+}
+
+#[test]
+fn test_evaluation_context() {
+    let mut s = SymbolTable::new();
+    let a_id = s.add_variable("a", 1.).unwrap().unwrap();
+    let s_id = s.add_stringvar("s", "x").unwrap().unwrap();
+    let v_id = s.add_vector("v", &[1., 2.]).unwrap().unwrap();
+    let w_id = s.add_vector_view("w", 3).unwrap().unwrap();
+    let expr = Expression::new("v[0] := a + s[]; v[0] + v[1] + sum(w)", s).unwrap();
+    let mut ctx = EvaluationContext::new(expr);
+
+    let mut store1 = ctx.new_store();
+    let mut store2 = ctx.new_store();
+    *store2.value_mut(a_id) = 10.;
+    store2.set_string(s_id, "xyz");
+    store2.vector_mut(v_id)[1] = 5.;
+
+    assert_relative_eq!(ctx.value(&mut store1), 4.);
+    assert_relative_eq!(ctx.value(&mut store2), 18.);
+    // the assignment is stored in the respective store
+    assert_eq!(store1.vector(v_id), &[2., 2.]);
+    assert_eq!(store2.vector(v_id), &[13., 5.]);
+    assert_eq!(store2.string(s_id), "xyz");
+    // the symbol table is unchanged
+    assert_eq!(ctx.expression().symbols().vector(v_id), &[1., 2.]);
+    assert_eq!(ctx.expression().symbols().string(s_id).get(), "x");
+
+    // vector views are rebound instead of copied
+    *store2.vector_view_mut(w_id) = vec![1., 2.];
+    let data = store2.vector_view(w_id).as_ptr();
+    ctx.swap(&mut store2);
+    assert_eq!(ctx.expression().symbols().value(a_id), 10.);
+    assert_eq!(store2.value(a_id), 1.);
+    assert_eq!(store2.vector_view(w_id), &[0., 0., 0.]);
+    let view = ctx.expression().symbols().vector_view(w_id);
+    assert_eq!(view.as_slice().as_ptr(), data);
+    assert_eq!(view.len(), 2);
+    assert_relative_eq!(ctx.expression_mut().value(), 21.);
+}
+
+#[test]
+fn test_vector_view() {
+    let mut s = SymbolTable::new();
+    let v_id = s.add_vector("v", &[1.]).unwrap().unwrap();
+    let w_id = s.add_vector_view("w", 3).unwrap().unwrap();
+    assert_eq!(s.add_vector_view("w", 3), Ok(None));
+    assert_eq!(s.get_vector_view_id("w").unwrap(), Some(w_id));
+    assert_eq!(s.get_vec_id("w").unwrap(), None);
+    assert_eq!(s.get_vec_id("v").unwrap(), Some(v_id));
+    assert_eq!(s.vector_view(w_id).as_slice(), &[0., 0., 0.]);
+
+    s.vector_view_mut(w_id)
+        .as_mut_slice()
+        .copy_from_slice(&[1., 2., 3.]);
+    let mut expr = Expression::new("sum(w) + w[] + v[0]", s).unwrap();
+    assert_relative_eq!(expr.value(), 10.);
+
+    let w = expr.symbols_mut().vector_view_mut(w_id);
+    assert!(w.set_len(1));
+    assert!(!w.set_len(0));
+    assert!(!w.set_len(4));
+    assert_eq!(w.len(), 1);
+    assert_relative_eq!(expr.value(), 3.);
+
+    let w = expr.symbols_mut().vector_view_mut(w_id);
+    assert_eq!(w.rebind(vec![4., 5.]), vec![1., 2., 3.]);
+    assert_eq!(w.capacity(), 3);
+    // the buffer is shorter than the capacity
+    assert!(!w.set_len(3));
+    assert_relative_eq!(expr.value(), 12.);
+
+    let mut cloned = expr.clone();
+    assert_relative_eq!(cloned.value(), 12.);
+    assert_eq!(cloned.symbols().vector_view(w_id).as_slice(), &[4., 5.]);
+    assert_eq!(format!("{:?}", expr), format!("{:?}", cloned));
+}
+
+#[test]
+fn test_constants_str_vec() {
+    let mut s = SymbolTable::new();
+    assert_eq!(s.add_string_constant("label", "abc"), Ok(true));
+    assert_eq!(s.add_string_constant("label", "def"), Ok(false));
+    assert_eq!(s.add_vector_constant("table", &[1., 2., 3.]), Ok(true));
+    s.add_vector("v", &[1., 2.]).unwrap();
+    assert!(s.is_constant_string("label").unwrap());
+    assert!(s.is_constant_vector("table").unwrap());
+    assert!(!s.is_constant_vector("v").unwrap());
+    assert_eq!(s.get_string_id("label").unwrap(), None);
+    assert_eq!(s.get_vec_id("table").unwrap(), None);
+
+    assert!(Expression::new("label := 'x'", s.clone()).is_err());
+    assert!(Expression::new("table[0] := 2", s.clone()).is_err());
+    assert!(Expression::new("table := v", s.clone()).is_err());
+
+    let mut expr = Expression::new("v := table; (label == 'abc') + table[2]", s).unwrap();
+    assert_relative_eq!(expr.value(), 4.);
+    assert!(expr.recompile("table[1] += 1").is_err());
+    assert_eq!(expr.formula(), "v := table; (label == 'abc') + table[2]");
+
+    let mut cloned = expr.clone();
+    assert!(cloned.symbols().is_constant_string("label").unwrap());
+    assert!(cloned.symbols().is_constant_vector("table").unwrap());
+    assert_relative_eq!(cloned.value(), 4.);
+    assert_eq!(format!("{:?}", expr), format!("{:?}", cloned));
+}
+
+#[test]
+fn test_function_list() {
+    let mut s = SymbolTable::new();
+    assert!(s.functions().is_empty());
+    s.add_func1("f", |x| x).unwrap();
+    s.add_func3("g", |x, y, z| x + y + z).unwrap();
+    let mut names = s.function_names();
+    names.sort();
+    assert_eq!(names, vec!["f".to_string(), "g".to_string()]);
+
+    let mut funcs = s.clone().functions();
+    funcs.sort_by(|a, b| a.name.cmp(&b.name));
+    assert_eq!(
+        funcs[1],
+        FunctionInfo {
+            name: "g".to_string(),
+            arity: Some(3),
+            kind: FunctionKind::Scalar,
+            is_pure: true,
+        }
+    );
+    assert_eq!(funcs[0].arity, Some(1));
+}
+
+#[test]
+fn test_builtins() {
+    let words = reserved_words();
+    assert!(words.contains(&"for".to_string()));
+    assert!(words.contains(&"swap".to_string()));
+    // reserved independently of the features
+    assert!(words.contains(&"break".to_string()));
+    assert!(is_reserved_name("return"));
+    let funcs = base_functions();
+    assert!(funcs.contains(&"clamp".to_string()));
+    assert!(funcs.contains(&"inrange".to_string()));
+    assert!(operators().contains(&":=".to_string()));
+    assert!(is_reserved_name("while"));
+    assert!(!is_reserved_name("x"));
+
+    let mut s = SymbolTable::new();
+    assert!(s.add_variable("sin", 1.).is_err());
+    #[cfg(any(feature = "rtl_vecops", feature = "all"))]
+    {
+        assert!(vecops_functions().contains(&"sort".to_string()));
+        assert!(s.add_vecops_package());
+        s.add_vector("v", &[3., 1., 2.]).unwrap();
+        let mut expr = Expression::new("sort(v); v[0]", s.clone()).unwrap();
+        assert_relative_eq!(expr.value(), 1.);
+    }
+}
+
+#[test]
+fn test_tokenize() {
+    let formula = "var x := [1]; x += 'a\\'b' /* c */ [2] >= f(x)";
+    let tokens = tokenize(formula).unwrap();
+    let summary: Vec<_> = tokens
+        .iter()
+        .map(|t| (t.kind, &formula[t.span.clone()]))
+        .collect();
+    let mut expected = vec![
+        (TokenKind::Symbol, "var"),
+        (TokenKind::Symbol, "x"),
+        (TokenKind::Assignment, ":="),
+        (TokenKind::Bracket, "["),
+        (TokenKind::Number, "1"),
+        (TokenKind::Bracket, "]"),
+        (TokenKind::Separator, ";"),
+        (TokenKind::Symbol, "x"),
+        (TokenKind::Assignment, "+="),
+        (TokenKind::String, "'a\\'b'"),
+    ];
+    // the comment is skipped
+    expected.extend(vec![
+        (TokenKind::Bracket, "["),
+        (TokenKind::Number, "2"),
+        (TokenKind::Bracket, "]"),
+        (TokenKind::Operator, ">="),
+        (TokenKind::Symbol, "f"),
+        (TokenKind::Bracket, "("),
+        (TokenKind::Symbol, "x"),
+        (TokenKind::Bracket, ")"),
+    ]);
+    if cfg!(any(feature = "comments", feature = "all")) {
+        assert_eq!(summary, expected);
+    }
+    assert_eq!(tokens[9].value, "a'b");
+
+    // tokens are joined and inserted like in the parser
+    let formula = "2x + y : = 1";
+    let tokens = tokenize(formula).unwrap();
+    let summary: Vec<_> = tokens
+        .iter()
+        .map(|t| (t.kind, t.value.as_str(), &formula[t.span.clone()]))
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            (TokenKind::Number, "2", "2"),
+            (TokenKind::Operator, "*", ""),
+            (TokenKind::Symbol, "x", "x"),
+            (TokenKind::Operator, "+", "+"),
+            (TokenKind::Symbol, "y", "y"),
+            (TokenKind::Assignment, ":=", ": ="),
+            (TokenKind::Number, "1", "1"),
+        ]
+    );
+    assert_eq!(tokens[1].span, 1..1);
+
+    // like in the parser, true and false are replaced by numbers
+    let formula = "x == true";
+    let t = tokenize(formula).unwrap().pop().unwrap();
+    assert_eq!((t.kind, t.value.as_str()), (TokenKind::Number, "1"));
+    assert_eq!(&formula[t.span], "true");
+
+    let tokens = tokenize("1 + 'abc").unwrap();
+    assert_eq!(tokens.last().unwrap().kind, TokenKind::Error);
+    assert!(tokenize("a\0").is_err());
+}
+
+#[test]
+fn test_rename_symbols() {
+    let formula = "var xy := x; x[0] + f(x, 'x') + xx";
+    let renamed = rename_symbols(formula, |n| match n {
+        "x" => Some("col_1".to_string()),
+        "f" => Some("g".to_string()),
+        _ => None,
+    })
+    .unwrap();
+    assert_eq!(renamed, "var xy := col_1; col_1[0] + g(col_1, 'x') + xx");
+    assert_eq!(rename_symbols(formula, |_| None).unwrap(), formula);
+    // keywords and built-in functions are not renamed
+    let renamed = rename_symbols("var y := sin(x); for", |n| Some(format!("{}_", n))).unwrap();
+    assert_eq!(renamed, "var y_ := sin(x_); for");
+
+    assert!(rename_symbols("x + 1", |_| Some("a b".to_string())).is_err());
+    assert!(rename_symbols("x + 1", |_| Some("".to_string())).is_err());
+    assert!(rename_symbols("x + 'abc", |_| None).is_err());
+}
+
+#[test]
+fn test_name_mapper() {
+    let mut m = NameMapper::new();
+    let mut s = SymbolTable::new();
+    let a_id = m.add_variable(&mut s, "a b", 1.).unwrap().unwrap();
+    m.add_variable(&mut s, "a.b", 2.).unwrap();
+    m.add_variable(&mut s, "a_b", 3.).unwrap();
+    m.add_variable(&mut s, "x", 4.).unwrap();
+    m.add_stringvar(&mut s, "µ", "").unwrap();
+    m.add_vector(&mut s, "for", &[1.]).unwrap();
+    assert_eq!(m.identifier("a b"), Some("a_b"));
+    assert_eq!(m.identifier("a.b"), Some("a_b_2"));
+    assert_eq!(m.identifier("a_b"), Some("a_b_3"));
+    assert_eq!(m.identifier("x"), Some("x"));
+    assert_eq!(m.identifier("µ"), Some("v__"));
+    assert_eq!(m.identifier("for"), Some("for_"));
+    assert_eq!(m.identifier("y"), None);
+    assert_eq!(m.original("a_b_2"), Some("a.b"));
+    assert_eq!(m.len(), 6);
+    // mapping is stable
+    assert_eq!(m.map("a.b"), "a_b_2");
+
+    let mut expr = Expression::new("a_b + a_b_2 * x", s).unwrap();
+    assert_relative_eq!(expr.value(), 9.);
+    assert_eq!(expr.symbols().value(a_id), 1.);
+    let mut names = m.original_names(expr.symbols().get_variable_names());
+    names.sort();
+    assert_eq!(names, vec!["a b", "a.b", "a_b", "x"]);
+
+    // symbols already present in the table are avoided
+    let mut s = SymbolTable::new();
+    s.add_constants();
+    m.add_variable(&mut s, "pi", 1.).unwrap().unwrap();
+    assert_eq!(m.identifier("pi"), Some("pi_2"));
+    assert_eq!(m.map("e"), "e");
+
+    assert_eq!(
+        m.translate_message("Undefined symbol: 'a_b_2' (a_b_22)"),
+        "Undefined symbol: 'a.b' (a_b_22)"
+    );
+    let err = Expression::new("a_b_3 + 1", SymbolTable::new()).unwrap_err();
+    let err = m.translate_error(err);
+    assert!(err.message.contains("'a_b'"));
+    assert!(!err.message.contains("a_b_3"));
+}
+
+#[test]
+fn test_derivative() {
+    let mut s = SymbolTable::new();
+    let x_id = s.add_variable("x", 0.5).unwrap().unwrap();
+    let y_id = s.add_variable("y", 2.).unwrap().unwrap();
+    let mut expr = Expression::new("x^4 + x * y", s.clone()).unwrap();
+
+    assert_relative_eq!(expr.derivative(x_id, 1., 1e-3), 6., epsilon = 1e-8);
+    assert_relative_eq!(expr.second_derivative(x_id, 1., 1e-3), 12., epsilon = 1e-4);
+    assert_relative_eq!(expr.third_derivative(x_id, 1., 1e-2), 24., epsilon = 1e-2);
+    assert_eq!(expr.symbols().value(x_id), 0.5);
+
+    let grad = expr.gradient(&[x_id, y_id], 1e-3);
+    assert_relative_eq!(grad[0], 2.5, epsilon = 1e-8);
+    assert_relative_eq!(grad[1], 0.5, epsilon = 1e-8);
+
+    let mut exprs = vec![expr, Expression::new("y / x", s).unwrap()];
+    let jac = jacobian(&mut exprs, &[x_id, y_id], &[1., 1.], 1e-3);
+    assert_relative_eq!(jac[0][0], 5., epsilon = 1e-8);
+    assert_relative_eq!(jac[0][1], 1., epsilon = 1e-8);
+    assert_relative_eq!(jac[1][0], -1., epsilon = 1e-6);
+    assert_relative_eq!(jac[1][1], 1., epsilon = 1e-8);
+    assert_eq!(exprs[1].symbols().value(x_id), 0.5);
+    assert_eq!(exprs[1].symbols().value(y_id), 2.);
+}
+
+#[test]
+fn test_integrate() {
+    let mut s = SymbolTable::new();
+    let x_id = s.add_variable("x", 7.).unwrap().unwrap();
+    let mut expr = Expression::new("sin(x)", s).unwrap();
+    let pi = std::f64::consts::PI;
+
+    assert_relative_eq!(expr.integrate(x_id, 0., pi, 1000), 2., epsilon = 1e-10);
+    assert_relative_eq!(expr.integrate(x_id, pi, 0., 1000), -2., epsilon = 1e-10);
+    assert_eq!(expr.symbols().value(x_id), 7.);
+
+    let res = expr.integrate_simpson(x_id, 0., pi, 1e-10).unwrap();
+    assert_relative_eq!(res.value, 2., epsilon = 1e-10);
+    assert!(res.error <= 1e-10);
+    assert!(res.evaluations > 3);
+    assert_eq!(expr.symbols().value(x_id), 7.);
+    let res = expr.integrate_simpson(x_id, pi, 0., 1e-10).unwrap();
+    assert_relative_eq!(res.value, -2., epsilon = 1e-10);
+    // the tolerance cannot be reached
+    let res = expr.integrate_simpson(x_id, 0., pi, 0.);
+    assert!(matches!(
+        res,
+        Err(IntegrationError::TooManyEvaluations { evaluations }) if evaluations <= 100_000
+    ));
+    assert_eq!(expr.symbols().value(x_id), 7.);
+
+    let res = expr.integrate_gauss_kronrod(x_id, 0., pi, 1e-12).unwrap();
+    assert_relative_eq!(res.value, 2., epsilon = 1e-12);
+    assert!(res.error <= 1e-12);
+    assert_eq!(res.evaluations % 15, 0);
+    assert_eq!(expr.symbols().value(x_id), 7.);
+
+    // the tolerance cannot be reached due to the singularity
+    expr.recompile("1 / sqrt(x)").unwrap();
+    let res = expr.integrate_gauss_kronrod(x_id, 0., 1., 0.).unwrap();
+    assert_relative_eq!(res.value, 2., epsilon = 1e-6);
+    assert!(res.error > 0.);
+    // 1000 intervals after 999 bisections
+    assert_eq!(res.evaluations, 15 * 1999);
+    let res = expr.integrate_simpson(x_id, 0., 1., 1e-6);
+    assert_eq!(res, Err(IntegrationError::NotFinite { x: 0. }));
+
+    expr.recompile("1 / (x - 0.5)").unwrap();
+    let res = expr.integrate_gauss_kronrod(x_id, 0., 1., 1e-6);
+    assert_eq!(res, Err(IntegrationError::NotFinite { x: 0.5 }));
+    assert_eq!(expr.symbols().value(x_id), 7.);
+}
+
+#[test]
+#[should_panic(expected = "must not be zero")]
+fn test_integrate_zero_steps() {
+    let mut s = SymbolTable::new();
+    let x_id = s.add_variable("x", 0.).unwrap().unwrap();
+    let mut expr = Expression::new("x", s).unwrap();
+    expr.integrate(x_id, 0., 1., 0);
+}
+
+#[test]
+fn test_solver() {
+    let mut s = SymbolTable::new();
+    let x_id = s.add_variable("x", 5.).unwrap().unwrap();
+    let mut expr = Expression::new("cos(x) - x", s).unwrap();
+    let opts = SolverOptions::default();
+    let root = 0.739_085_133_215_160_6;
+
+    let sol = expr.solve_brent(x_id, 0., 0., 1., opts).unwrap();
+    assert_relative_eq!(sol.x, root, epsilon = 1e-10);
+    assert!(sol.fx.abs() < 1e-9);
+    assert_eq!(expr.symbols().value(x_id), 5.);
+
+    let sol = expr.solve_newton(x_id, 0., 1., opts).unwrap();
+    assert_relative_eq!(sol.x, root, epsilon = 1e-10);
+    assert!(sol.iterations < 10);
+    assert_eq!(expr.symbols().value(x_id), 5.);
+
+    // target value
+    let sol = expr.solve_brent(x_id, -1., 0., 3., opts).unwrap();
+    assert_relative_eq!(sol.x.cos() - sol.x, -1., epsilon = 1e-9);
+
+    match expr.solve_brent(x_id, 0., 1., 2., opts) {
+        Err(SolverError::NoBracket { a, b, .. }) => assert_eq!((a, b), (1., 2.)),
+        r => panic!("unexpected result: {:?}", r),
+    }
+    let few = SolverOptions {
+        max_iter: 2,
+        ..opts
+    };
+    match expr.solve_newton(x_id, 0., 10., few) {
+        Err(SolverError::NotConverged { iterations, .. }) => assert_eq!(iterations, 2),
+        r => panic!("unexpected result: {:?}", r),
+    }
+
+    expr.recompile("x^2 - 4").unwrap();
+    assert_eq!(
+        expr.solve_newton(x_id, 0., 0., opts),
+        Err(SolverError::ZeroDerivative { x: 0. })
+    );
+    expr.recompile("1 / x").unwrap();
+    assert!(matches!(
+        expr.solve_brent(x_id, 0., 0., 1., opts),
+        Err(SolverError::NotFinite { .. })
+    ));
+
+    expr.recompile("(x - 2)^2 + 1").unwrap();
+    let sol = expr.minimize_golden(x_id, 0., 10., opts).unwrap();
+    assert_relative_eq!(sol.x, 2., epsilon = 1e-6);
+    assert_relative_eq!(sol.fx, 1., epsilon = 1e-12);
+    assert_eq!(expr.symbols().value(x_id), 5.);
+}
+
+#[test]
+fn test_fit() {
+    let x: Vec<f64> = (0..10).map(f64::from).collect();
+    let noise = [0.1, -0.2, 0.05, 0.15, -0.1, 0., 0.2, -0.15, 0.1, -0.05];
+    let y: Vec<_> = x.iter().zip(&noise).map(|(x, e)| 3. * x + 1. + e).collect();
+    let opts = SolverOptions::default();
+
+    let res = fit_curve("a * x + b", SymbolTable::new(), &[("x", &x)], &y, &[], opts).unwrap();
+    assert_eq!(res.params.len(), 2);
+    assert_relative_eq!(res.param("a").unwrap(), 3., epsilon = 1e-6);
+    assert_relative_eq!(res.param("b").unwrap(), 1.01, epsilon = 1e-6);
+    assert_eq!(res.param("x"), None);
+    assert_relative_eq!(res.sum_of_squares(), 0.159, epsilon = 1e-9);
+    for ((f, r), y) in res.fitted.iter().zip(&res.residuals).zip(&y) {
+        assert_relative_eq!(f + r, y, epsilon = 1e-12);
+    }
+    // variance of the slope: s^2 / sum((x - mean)^2)
+    assert_relative_eq!(res.covariance[0][0], 0.159 / 8. / 82.5, max_relative = 1e-5);
+    assert_relative_eq!(res.covariance[0][1], res.covariance[1][0], epsilon = 1e-12);
+
+    // functions from the symbol table
+    let mut s = SymbolTable::new();
+    s.add_constants();
+    let y2: Vec<_> = x
+        .iter()
+        .map(|x| (x / std::f64::consts::PI * 0.5).sin() * 4.)
+        .collect();
+    let res = fit_curve(
+        "amp * sin(k * x / pi)",
+        s,
+        &[("x", &x)],
+        &y2,
+        &[("k", 0.6)],
+        opts,
+    )
+    .unwrap();
+    assert_relative_eq!(res.param("amp").unwrap(), 4., epsilon = 1e-6);
+    assert_relative_eq!(res.param("k").unwrap(), 0.5, epsilon = 1e-6);
+
+    assert_eq!(
+        fit_curve(
+            "a * x + b",
+            SymbolTable::new(),
+            &[("x", &x[..2])],
+            &y[..2],
+            &[],
+            opts
+        ),
+        Err(FitError::TooFewObservations {
+            observations: 2,
+            parameters: 2
+        })
+    );
+    assert!(matches!(
+        fit_curve("a * b * x", SymbolTable::new(), &[("x", &x)], &y, &[], opts),
+        Err(FitError::Singular)
+    ));
+    assert!(matches!(
+        fit_curve("a * x +", SymbolTable::new(), &[("x", &x)], &y, &[], opts),
+        Err(FitError::Parse(_))
+    ));
+
+    // invalid input
+    let fit = |inputs: &[(&str, &[f64])], initial: &[(&str, f64)]| {
+        let mut s = SymbolTable::new();
+        s.add_variable("z", 0.).unwrap();
+        fit_curve("a * x + b", s, inputs, &y, initial, opts)
+    };
+    assert_eq!(
+        fit(&[("x", &x[..3])], &[]),
+        Err(FitError::LengthMismatch {
+            input: "x".to_string(),
+            len: 3,
+            observations: x.len()
+        })
+    );
+    assert_eq!(
+        fit(&[("x", &x), ("z", &x)], &[]),
+        Err(FitError::InputExists("z".to_string()))
+    );
+    assert_eq!(
+        fit(&[("x", &x)], &[("c", 1.)]),
+        Err(FitError::UnknownParameter("c".to_string()))
+    );
+}
+
+#[test]
+fn test_table_func() {
+    let xs = [0., 1., 2., 3., 4.];
+    let ys = [0., 1., 4., 9., 16.];
+    let mut s = SymbolTable::new();
+    let x_id = s.add_variable("x", 0.).unwrap().unwrap();
+    s.add_table_func("step", &xs, &ys, Interpolation::Step, Extrapolation::Clamp)
+        .unwrap();
+    s.add_table_func(
+        "lin",
+        &xs,
+        &ys,
+        Interpolation::Linear,
+        Extrapolation::Linear,
+    )
+    .unwrap();
+    s.add_table_func(
+        "spl",
+        &xs,
+        &ys,
+        Interpolation::CubicSpline,
+        Extrapolation::NaN,
+    )
+    .unwrap();
+    s.add_table_func2(
+        "bilin",
+        &[0., 1.],
+        &[0., 10.],
+        &[0., 1., 2., 3.],
+        Extrapolation::Clamp,
+    )
+    .unwrap();
+    assert_eq!(
+        s.add_table_func("lin", &xs, &ys, Interpolation::Linear, Extrapolation::Clamp),
+        Ok(false)
+    );
+    let x = [2.5, 0.5, 4., -1., f64::NAN];
+    let eval = |formula: &str| {
+        let mut expr = Expression::new(formula, s.clone()).unwrap();
+        let mut out = [0.; 5];
+        expr.eval_batch(&[(x_id, &x)], &mut out);
+        out
+    };
+    let step = eval("step(x)");
+    assert_eq!(step[..4], [4., 0., 16., 0.]);
+    let lin = eval("lin(x)");
+    assert_eq!(lin[..4], [6.5, 0.5, 16., -1.]);
+    let spl = eval("spl(x)");
+    assert_relative_eq!(spl[0], 6.232_142_857_142_857, epsilon = 1e-12);
+    assert_eq!(spl[2], 16.);
+    assert!(spl[3].is_nan());
+    let bilin = eval("bilin(x, 10 * x)");
+    assert_eq!(bilin[..4], [3., 1.5, 3., 0.]);
+    assert!([step, lin, spl, bilin].iter().all(|v| v[4].is_nan()));
+
+    let mut s = SymbolTable::new();
+    let (lin, clamp) = (Interpolation::Linear, Extrapolation::Clamp);
+    assert_eq!(
+        s.add_table_func("f", &[0.], &[1.], lin, clamp),
+        Err(TableError::TooFewPoints {
+            points: 1,
+            required: 2
+        })
+    );
+    assert_eq!(
+        s.add_table_func("f", &[0.], &[1.], Interpolation::Step, clamp),
+        Ok(true)
+    );
+    assert_eq!(
+        s.add_table_func("g", &[0., 1.], &[1.], lin, clamp),
+        Err(TableError::LengthMismatch {
+            expected: 2,
+            found: 1
+        })
+    );
+    assert_eq!(
+        s.add_table_func("g", &[0., 1., 1.], &[1., 2., 3.], lin, clamp),
+        Err(TableError::NotIncreasing { index: 2 })
+    );
+    assert_eq!(
+        s.add_table_func("g", &[0., 1.], &[1., f64::INFINITY], lin, clamp),
+        Err(TableError::NotFinite { index: 1 })
+    );
+    assert_eq!(
+        s.add_table_func2("g", &[0., 1.], &[0., 1.], &[1., 2., 3.], clamp),
+        Err(TableError::LengthMismatch {
+            expected: 4,
+            found: 3
+        })
+    );
+    assert!(matches!(
+        s.add_table_func("1g", &[0., 1.], &[1., 2.], lin, clamp),
+        Err(TableError::InvalidName(_))
+    ));
+}
+
+#[test]
+fn test_polynomial() {
+    let mut s = SymbolTable::new();
+    let x_id = s.add_variable("x", 2.).unwrap().unwrap();
+    assert_eq!(s.add_polynomial("c", &[5.]), Ok(true));
+    assert_eq!(s.add_polynomial("p", &[1., -3., 2.]), Ok(true));
+    assert_eq!(s.add_polynomial("p", &[1.]), Ok(false));
+    assert!(s.add_polynomial("1p", &[1.]).is_err());
+    // 2^12 + 2^11 + ... + 1
+    s.add_polynomial("p12", &[1.; MAX_POLYNOMIAL_DEGREE + 1])
+        .unwrap();
+    let info = s.functions().into_iter().find(|f| f.name == "p").unwrap();
+    assert_eq!(info.arity, Some(1));
+
+    let mut expr = Expression::new("c(x) + p(x) + p12(x)", s).unwrap();
+    assert_eq!(expr.value(), 5. + 0. + 8191.);
+    *expr.symbols_mut().value_mut(x_id) = 4.;
+    assert_eq!(expr.value(), 5. + 6. + 22369621.);
+    let mut expr2 = expr.clone();
+    assert_eq!(expr2.value(), expr.value());
+    assert!(format!("{:?}", expr2.symbols()).contains("p12"));
+
+    let mut s = SymbolTable::<f32>::default();
+    s.add_polynomial("p", &[0.5f32, 1.]).unwrap();
+    let mut expr = Expression::new("p(3)", s).unwrap();
+    assert_eq!(expr.value(), 2.5f32);
+}
+
+#[test]
+#[should_panic(expected = "1 to 13 coefficients")]
+fn test_polynomial_degree() {
+    let mut s = SymbolTable::new();
+    s.add_polynomial("p", &[1.; MAX_POLYNOMIAL_DEGREE + 2])
+        .unwrap();
+}
+
+#[cfg(any(feature = "rtl_io", feature = "all"))]
+#[test]
+fn test_io_package() {
+    let output = OutputBuffer::new();
+    let mut s = SymbolTable::new();
+    let x_id = s.add_variable("x", 2.).unwrap().unwrap();
+    s.add_vector("v", &[1., -2.5]).unwrap();
+    assert!(s.add_io_package(output.clone()));
+    assert!(!s.add_io_package(OutputBuffer::new()));
+    let mut expr = Expression::new("print('x=', x, ' '); println(v); println(); x", s).unwrap();
+
+    assert_eq!(expr.value(), 2.);
+    assert_eq!(output.contents(), "x=   2.00000    1.00000   -2.50000\n\n");
+    *expr.symbols_mut().value_mut(x_id) = 3.;
+    let (value, printed) = expr.value_with_output();
+    assert_eq!(value, 3.);
+    assert!(printed.starts_with("x=   3.00000 "));
+    // the output of value_with_output() does not reach the sink
+    assert_eq!(output.take().len(), 36);
+
+    // clones share the sink
+    let mut expr2 = expr.clone();
+    expr2.value();
+    assert!(output.take().starts_with("x=   3.00000"));
+
+    // replacing the sink after compilation
+    let other = OutputBuffer::new();
+    assert!(expr.symbols_mut().set_io_sink(other.clone()));
+    expr.value();
+    assert!(output.is_empty());
+    assert!(!other.is_empty());
+
+    let mut s = SymbolTable::new();
+    assert!(!s.set_io_sink(OutputBuffer::new()));
+    let mut expr = Expression::new("1", s).unwrap();
+    assert_eq!(expr.value_with_output(), (1., String::new()));
+    assert!(Expression::new("println(1)", SymbolTable::new()).is_err());
+}